@@ -81,7 +81,7 @@ impl Renderable<Checkbox> for Checkbox {
         html! {
             <div class="checkbox",>
                 <ion_checkbox
-                    checked=self.value,
+                    .checked=self.value,
                     [ionChange]=|_| Msg::Click,
                 />
                 <div class="checkbox-label",>{&self.label}</div>
@@ -149,7 +149,7 @@ impl Renderable<TextField> for TextField {
                     <ion_input
                         type=ty,
                         placeholder=&self.label,
-                        value=&self.value,
+                        .value=self.value.as_str(),
                         [ionChange]=|event: Event| {
                             let c: web_sys::CustomEvent = event.dyn_into().expect("is not custom event");
                             let detail: Detail = c.detail().into_serde().unwrap();
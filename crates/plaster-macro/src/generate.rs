@@ -0,0 +1,236 @@
+//! Generation of the code which builds a virtual tree of a template.
+//!
+//! The generated code calls the same `VTag`/`VComp` methods as `html_impl!`,
+//! calls are spanned to the values of attributes, so type errors point at them.
+
+use crate::parse::{Attribute, Component, Element, Node};
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{parse_quote, ArgCaptured, Expr, FnArg, Ident};
+
+/// Generates an expression which evaluates to a `VNode`.
+pub fn node(node: Node) -> syn::Result<TokenStream> {
+    match node {
+        Node::Element(element) => self::element(element),
+        Node::Component(component) => self::component(*component),
+        Node::Fragment(children) => {
+            let children = self::children(children)?;
+            Ok(quote! {{
+                let mut __plaster_vlist = ::plaster::virtual_dom::VList::new();
+                #(__plaster_vlist.add_child(#children);)*
+                ::plaster::virtual_dom::VNode::from(__plaster_vlist)
+            }})
+        }
+        Node::Expr(expr) => Ok(quote! {
+            ::plaster::virtual_dom::VNode::from(#expr)
+        }),
+        Node::Iter(expr) => Ok(quote! {{
+            let mut __plaster_vlist = ::plaster::virtual_dom::VList::new();
+            for __plaster_node in #expr {
+                __plaster_vlist.add_child(::plaster::virtual_dom::VNode::from(__plaster_node));
+            }
+            ::plaster::virtual_dom::VNode::from(__plaster_vlist)
+        }}),
+        Node::Text(text) => Ok(quote! {
+            ::plaster::virtual_dom::VNode::from(#text)
+        }),
+    }
+}
+
+fn children(children: Vec<Node>) -> syn::Result<Vec<TokenStream>> {
+    children.into_iter().map(node).collect()
+}
+
+fn element(element: Element) -> syn::Result<TokenStream> {
+    let tag = element.name.value;
+    let mut setters = Vec::new();
+    for attribute in element.attributes {
+        setters.push(self::attribute(&tag, attribute)?);
+    }
+    let children = self::children(element.children)?;
+    Ok(quote! {{
+        let mut __plaster_vtag = ::plaster::virtual_dom::VTag::new(#tag);
+        #(#setters)*
+        #(__plaster_vtag.add_child(#children);)*
+        ::plaster::virtual_dom::VNode::from(__plaster_vtag)
+    }})
+}
+
+fn attribute(tag: &str, attribute: Attribute) -> syn::Result<TokenStream> {
+    let name = attribute.name.value;
+    let value = attribute.value;
+    let span = value.span();
+    if attribute.property {
+        return Ok(quote_spanned! {span=>
+            __plaster_vtag.set_property(#name, #value);
+        });
+    }
+    let modifiers = attribute.modifiers;
+    if attribute.generic_event {
+        let listener = quote_spanned! {span=>
+            ::plaster::html::GenericAction::new(#name, #value)
+        };
+        return Ok(add_listener(listener, modifiers));
+    }
+    if name.starts_with("on") {
+        // Every event has a listener module in `plaster::html`, so an unknown
+        // event fails to resolve at the span of its name.
+        let action = Ident::new(&name, attribute.name.span);
+        let handler = typed_handler(&action, value);
+        let listener = quote_spanned! {span=>
+            ::plaster::html::#action::Wrapper::from(#handler)
+        };
+        return Ok(add_listener(listener, modifiers));
+    }
+    if let Some(modifier) = modifiers.first() {
+        return Err(syn::Error::new(
+            modifier.span(),
+            "modifiers could be set for listeners of events only",
+        ));
+    }
+    let setter = match name.as_str() {
+        "class" => match value {
+            Expr::Tuple(tuple) => {
+                let classes = tuple.elems.into_iter();
+                quote_spanned! {span=>
+                    #(__plaster_vtag.add_class(::std::convert::AsRef::<str>::as_ref(&#classes));)*
+                }
+            }
+            Expr::Paren(paren) => {
+                let class = paren.expr;
+                quote_spanned! {span=>
+                    __plaster_vtag.add_class(::std::convert::AsRef::<str>::as_ref(&#class));
+                }
+            }
+            value => quote_spanned! {span=>
+                __plaster_vtag.set_classes(::std::convert::AsRef::<str>::as_ref(&#value));
+            },
+        },
+        "key" => quote_spanned! {span=>
+            __plaster_vtag.set_key(&#value);
+        },
+        "style" => quote_spanned! {span=>
+            __plaster_vtag.set_style(#value);
+        },
+        "ref" => quote_spanned! {span=>
+            __plaster_vtag.set_node_ref(&#value);
+        },
+        "value" if tag.eq_ignore_ascii_case("option") => quote_spanned! {span=>
+            __plaster_vtag.add_attribute("value", &#value);
+        },
+        "value" => quote_spanned! {span=>
+            __plaster_vtag.set_value(&#value);
+        },
+        "type" => quote_spanned! {span=>
+            let __plaster_kind = #value;
+            __plaster_vtag.set_kind(&__plaster_kind);
+            __plaster_vtag.add_attribute("type", &__plaster_kind);
+        },
+        "checked" => quote_spanned! {span=>
+            __plaster_vtag.set_checked(#value);
+        },
+        "disabled" => quote_spanned! {span=>
+            if #value {
+                __plaster_vtag.add_attribute("disabled", &"true");
+            }
+        },
+        "selected" => quote_spanned! {span=>
+            if #value {
+                __plaster_vtag.add_attribute("selected", &"selected");
+            }
+        },
+        "href" => quote_spanned! {span=>
+            let __plaster_href: ::plaster::html::Href = (#value).into();
+            __plaster_vtag.add_attribute("href", &__plaster_href);
+        },
+        _ => quote_spanned! {span=>
+            __plaster_vtag.add_attribute(#name, &#value);
+        },
+    };
+    Ok(setter)
+}
+
+/// Adds the listener to the tag, wrapped with its modifiers if there are any.
+/// Modifiers are fields of `Modifiers`, so unknown ones are reported at their spans.
+fn add_listener(listener: TokenStream, modifiers: Vec<Ident>) -> TokenStream {
+    if modifiers.is_empty() {
+        return quote! {
+            __plaster_vtag.add_listener(Box::new(#listener));
+        };
+    }
+    let modifiers = modifiers.into_iter().map(|modifier| {
+        quote_spanned! {modifier.span()=>
+            __plaster_modifiers.#modifier = true;
+        }
+    });
+    quote! {{
+        let mut __plaster_modifiers = ::plaster::html::Modifiers::default();
+        #(#modifiers)*
+        __plaster_vtag.add_listener(Box::new(
+            ::plaster::html::Modified::new(#listener, __plaster_modifiers)
+        ));
+    }}
+}
+
+/// Sets the type of arguments of a closure to the type of the event, so the closure
+/// doesn't need type annotations. Like `html_impl!` does it also moves captured values.
+fn typed_handler(action: &Ident, value: Expr) -> Expr {
+    match value {
+        Expr::Closure(mut closure) => {
+            let span = closure.span();
+            for input in closure.inputs.iter_mut() {
+                if let FnArg::Inferred(pat) = input.clone() {
+                    *input = FnArg::Captured(ArgCaptured {
+                        pat,
+                        colon_token: Default::default(),
+                        ty: parse_quote!(::plaster::html::#action::EventTy),
+                    });
+                }
+            }
+            if closure.capture.is_none() {
+                closure.capture = Some(syn::token::Move(span));
+            }
+            Expr::Closure(closure)
+        }
+        value => value,
+    }
+}
+
+fn component(component: Component) -> syn::Result<TokenStream> {
+    let ty = component.ty;
+    let with = component.with.into_iter();
+    let key = component.key.into_iter();
+    let props = component.props.into_iter().map(|(field, value)| {
+        quote_spanned! {value.span()=>
+            (__plaster_pair.0).#field = ::plaster::virtual_dom::vcomp::Transformer::transform(
+                &mut __plaster_pair.1,
+                #value,
+            );
+        }
+    });
+    let children = match component.children {
+        Some(children) => {
+            let children = self::children(children)?;
+            quote! {
+                __plaster_props.children = __plaster_comp.children();
+                let mut __plaster_vlist = ::plaster::virtual_dom::VList::new();
+                #(__plaster_vlist.add_child(#children);)*
+                __plaster_comp.set_children(__plaster_vlist);
+            }
+        }
+        None => TokenStream::new(),
+    };
+    Ok(quote! {{
+        #[allow(unused_mut)]
+        let mut __plaster_pair = ::plaster::virtual_dom::VComp::lazy::<#ty>();
+        #(__plaster_pair.0 = #with;)*
+        #(#props)*
+        #(__plaster_pair.1.set_key(&#key);)*
+        #[allow(unused_mut)]
+        let (mut __plaster_props, mut __plaster_comp) = __plaster_pair;
+        #children
+        __plaster_comp.set_props(__plaster_props);
+        ::plaster::virtual_dom::VNode::from(__plaster_comp)
+    }})
+}
@@ -0,0 +1,34 @@
+//! A procedural `html!` macro for `plaster`.
+//!
+//! It accepts the templates of the `html!` macro of `plaster`, but commas between
+//! attributes are optional and mismatched closing tags, unknown events and values
+//! of wrong types are reported at compile time with spans.
+
+extern crate proc_macro;
+
+mod generate;
+mod parse;
+
+use proc_macro::TokenStream;
+
+/// Builds a virtual tree of a template.
+///
+/// ```ignore
+/// html! {
+///     <div class="counter">
+///         <button onclick=|_| Msg::Increment>{ "+1" }</button>
+///         <Label: text=self.value.to_string() />
+///     </div>
+/// }
+/// ```
+#[proc_macro]
+pub fn html(input: TokenStream) -> TokenStream {
+    let template = match syn::parse::<parse::Template>(input) {
+        Ok(template) => template,
+        Err(error) => return error.to_compile_error().into(),
+    };
+    match generate::node(template.root) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
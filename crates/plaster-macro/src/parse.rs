@@ -0,0 +1,441 @@
+//! Parsing of `html!` templates.
+
+use proc_macro2::{Spacing, Span, TokenStream, TokenTree};
+use quote::quote;
+use syn::buffer::Cursor;
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream, Result};
+use syn::{braced, bracketed, token, Expr, Ident, LitStr, Token, Type};
+
+/// The root of a template, it has to be exactly one node.
+pub struct Template {
+    pub root: Node,
+}
+
+/// A node of a template.
+pub enum Node {
+    Element(Element),
+    Component(Box<Component>),
+    Fragment(Vec<Node>),
+    /// `{ expression }`
+    Expr(Expr),
+    /// `{ for expression }`
+    Iter(Expr),
+    /// `"text"`
+    Text(LitStr),
+}
+
+/// A tag of an element (ie. `<div class="main">...</div>`).
+pub struct Element {
+    pub name: Name,
+    pub attributes: Vec<Attribute>,
+    pub children: Vec<Node>,
+}
+
+/// A name of a tag or an attribute, dashed names like `aria-label` and
+/// prefixed names like `xlink:href` are allowed.
+pub struct Name {
+    pub value: String,
+    pub span: Span,
+}
+
+/// An attribute of an element (ie. `class="main"` or `[custom]=handler`).
+pub struct Attribute {
+    pub name: Name,
+    /// The attribute is a listener of a non-standard event written as `[name]=`.
+    pub generic_event: bool,
+    /// Modifiers of a listener written after the name (ie. `onclick.stop.prevent=`).
+    pub modifiers: Vec<Ident>,
+    /// The attribute is a JS property written as `.name=`.
+    pub property: bool,
+    pub value: Expr,
+}
+
+/// A tag of a component (ie. `<Item: label="first" />`).
+pub struct Component {
+    pub ty: Type,
+    /// The whole properties set with `with props`.
+    pub with: Option<Expr>,
+    pub key: Option<Expr>,
+    pub props: Vec<(Ident, Expr)>,
+    /// Markup between the opening and the closing tags.
+    pub children: Option<Vec<Node>>,
+}
+
+impl Parse for Template {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.is_empty() {
+            return Err(input.error("html! needs a root node"));
+        }
+        let root = input.parse()?;
+        if !input.is_empty() {
+            return Err(input.error(
+                "html! must have exactly one root node, wrap the nodes in a fragment: <>...</>",
+            ));
+        }
+        Ok(Template { root })
+    }
+}
+
+impl Parse for Node {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(token::Brace) {
+            let content;
+            braced!(content in input);
+            let iter = content.peek(Token![for]);
+            if iter {
+                content.parse::<Token![for]>()?;
+            }
+            let expr: Expr = content.parse()?;
+            if !content.is_empty() {
+                return Err(content.error("expected a single expression in braces"));
+            }
+            Ok(if iter {
+                Node::Iter(expr)
+            } else {
+                Node::Expr(expr)
+            })
+        } else if input.peek(LitStr) {
+            Ok(Node::Text(input.parse()?))
+        } else if input.peek(Token![<]) {
+            if input.peek2(Token![>]) {
+                parse_fragment(input)
+            } else if input.peek2(Token![/]) {
+                Err(input.error("closing tag without an opening tag"))
+            } else if is_component(input.cursor()) {
+                parse_component(input).map(|component| Node::Component(Box::new(component)))
+            } else {
+                parse_element(input).map(Node::Element)
+            }
+        } else {
+            Err(input.error("expected a tag, a string literal or an expression in braces"))
+        }
+    }
+}
+
+impl Parse for Name {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let first = Ident::parse_any(input)?;
+        let span = first.span();
+        let mut value = first.to_string();
+        loop {
+            if input.peek(Token![-]) {
+                input.parse::<Token![-]>()?;
+                value.push('-');
+            } else if input.peek(Token![:])
+                && !input.peek(Token![::])
+                && input.peek2(Ident::peek_any)
+            {
+                input.parse::<Token![:]>()?;
+                value.push(':');
+            } else {
+                break;
+            }
+            value.push_str(&Ident::parse_any(input)?.to_string());
+        }
+        Ok(Name { value, span })
+    }
+}
+
+fn parse_fragment(input: ParseStream) -> Result<Node> {
+    let open = input.parse::<Token![<]>()?;
+    input.parse::<Token![>]>()?;
+    let children = parse_children(input, open.spans[0])?;
+    input.parse::<Token![<]>()?;
+    input.parse::<Token![/]>()?;
+    if !input.peek(Token![>]) {
+        return Err(input.error("expected a closing tag of the fragment: </>"));
+    }
+    input.parse::<Token![>]>()?;
+    Ok(Node::Fragment(children))
+}
+
+fn parse_element(input: ParseStream) -> Result<Element> {
+    input.parse::<Token![<]>()?;
+    let name: Name = input.parse()?;
+    // Underscores are allowed in names of tags for compatibility with `html_impl!`
+    let name = Name {
+        value: name.value.replace("_", "-"),
+        span: name.span,
+    };
+    let mut attributes = Vec::new();
+    loop {
+        if input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+        } else if input.peek(Token![>]) || (input.peek(Token![/]) && input.peek2(Token![>])) {
+            break;
+        } else if input.peek(token::Bracket) {
+            let content;
+            bracketed!(content in input);
+            let name = content.parse()?;
+            let modifiers = parse_modifiers(input)?;
+            input.parse::<Token![=]>()?;
+            let value = parse_value(input)?;
+            attributes.push(Attribute {
+                name,
+                generic_event: true,
+                modifiers,
+                property: false,
+                value,
+            });
+        } else if input.peek(Token![.]) {
+            input.parse::<Token![.]>()?;
+            let name = input.parse()?;
+            input.parse::<Token![=]>()?;
+            let value = parse_value(input)?;
+            attributes.push(Attribute {
+                name,
+                generic_event: false,
+                modifiers: Vec::new(),
+                property: true,
+                value,
+            });
+        } else if input.is_empty() {
+            return Err(syn::Error::new(name.span, "this tag is not closed"));
+        } else {
+            let name = input.parse()?;
+            let modifiers = parse_modifiers(input)?;
+            input.parse::<Token![=]>()?;
+            let value = parse_value(input)?;
+            attributes.push(Attribute {
+                name,
+                generic_event: false,
+                modifiers,
+                property: false,
+                value,
+            });
+        }
+    }
+    if input.peek(Token![/]) {
+        input.parse::<Token![/]>()?;
+        input.parse::<Token![>]>()?;
+        return Ok(Element {
+            name,
+            attributes,
+            children: Vec::new(),
+        });
+    }
+    input.parse::<Token![>]>()?;
+    let children = parse_children(input, name.span)?;
+    input.parse::<Token![<]>()?;
+    input.parse::<Token![/]>()?;
+    let close: Name = input.parse()?;
+    if close.value.replace("_", "-") != name.value {
+        return Err(syn::Error::new(
+            close.span,
+            format!("expected the closing tag </{}>", name.value),
+        ));
+    }
+    input.parse::<Token![>]>()?;
+    Ok(Element {
+        name,
+        attributes,
+        children,
+    })
+}
+
+fn parse_component(input: ParseStream) -> Result<Component> {
+    input.parse::<Token![<]>()?;
+    let ty: Type = input.parse()?;
+    // The colon after the type is optional, it's required by `html_impl!`
+    if input.peek(Token![:]) && !input.peek(Token![::]) {
+        input.parse::<Token![:]>()?;
+    }
+    let mut component = Component {
+        ty,
+        with: None,
+        key: None,
+        props: Vec::new(),
+        children: None,
+    };
+    loop {
+        if input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+        } else if input.peek(Token![>]) || (input.peek(Token![/]) && input.peek2(Token![>])) {
+            break;
+        } else if input.is_empty() {
+            return Err(syn::Error::new_spanned(
+                &component.ty,
+                "this component is not closed",
+            ));
+        } else {
+            let name = Ident::parse_any(input)?;
+            if name == "with" && !input.peek(Token![=]) {
+                component.with = Some(parse_value(input)?);
+                continue;
+            }
+            input.parse::<Token![=]>()?;
+            let value = parse_value(input)?;
+            if name == "key" {
+                component.key = Some(value);
+            } else {
+                component.props.push((name, value));
+            }
+        }
+    }
+    if input.peek(Token![/]) {
+        input.parse::<Token![/]>()?;
+        input.parse::<Token![>]>()?;
+        return Ok(component);
+    }
+    input.parse::<Token![>]>()?;
+    let open_span = syn::spanned::Spanned::span(&component.ty);
+    component.children = Some(parse_children(input, open_span)?);
+    input.parse::<Token![<]>()?;
+    input.parse::<Token![/]>()?;
+    let close: Type = input.parse()?;
+    if type_name(&close) != type_name(&component.ty) {
+        return Err(syn::Error::new_spanned(
+            &close,
+            format!("expected the closing tag </{}>", type_name(&component.ty)),
+        ));
+    }
+    input.parse::<Token![>]>()?;
+    Ok(component)
+}
+
+/// Parses modifiers of a listener, each of them follows a dot.
+fn parse_modifiers(input: ParseStream) -> Result<Vec<Ident>> {
+    let mut modifiers = Vec::new();
+    while input.peek(Token![.]) {
+        input.parse::<Token![.]>()?;
+        modifiers.push(Ident::parse_any(input)?);
+    }
+    Ok(modifiers)
+}
+
+/// Parses nodes until a closing tag.
+fn parse_children(input: ParseStream, open: Span) -> Result<Vec<Node>> {
+    let mut children = Vec::new();
+    loop {
+        if input.is_empty() {
+            return Err(syn::Error::new(open, "this tag is not closed"));
+        }
+        if input.peek(Token![<]) && input.peek2(Token![/]) {
+            return Ok(children);
+        }
+        children.push(input.parse()?);
+    }
+}
+
+/// Components are named with a capital letter or with a path (ie. `<Item:` or `<items::Item:`).
+fn is_component(cursor: Cursor) -> bool {
+    let cursor = match cursor.punct() {
+        Some((_, cursor)) => cursor,
+        None => return false,
+    };
+    match cursor.ident() {
+        Some((ident, rest)) => {
+            let capital = ident
+                .to_string()
+                .chars()
+                .next()
+                .is_some_and(char::is_uppercase);
+            let path = match rest.punct() {
+                Some((punct, rest)) => {
+                    punct.as_char() == ':'
+                        && punct.spacing() == Spacing::Joint
+                        && rest
+                            .punct()
+                            .is_some_and(|(punct, _)| punct.as_char() == ':')
+                }
+                None => false,
+            };
+            capital || path
+        }
+        None => false,
+    }
+}
+
+fn type_name(ty: &Type) -> String {
+    quote!(#ty).to_string().replace(' ', "")
+}
+
+/// Parses a value of an attribute. The value ends with a comma, the end of the tag
+/// or the name of the next attribute, so values with `>` or `,` have to be wrapped
+/// in braces or parentheses.
+fn parse_value(input: ParseStream) -> Result<Expr> {
+    let tokens = input.step(|cursor| {
+        let mut rest = *cursor;
+        let mut tokens = TokenStream::new();
+        let mut joint = false;
+        while !ends_value(rest, joint, tokens.is_empty()) {
+            let (token, next) = rest.token_tree().expect("value ends at the end of input");
+            joint = match token {
+                TokenTree::Punct(ref punct) => punct.spacing() == Spacing::Joint,
+                _ => false,
+            };
+            tokens.extend(Some(token));
+            rest = next;
+        }
+        Ok((tokens, rest))
+    })?;
+    if tokens.is_empty() {
+        return Err(input.error("expected a value of the attribute"));
+    }
+    syn::parse2(tokens)
+}
+
+fn ends_value(cursor: Cursor, joint: bool, empty: bool) -> bool {
+    if cursor.eof() {
+        return true;
+    }
+    if let Some((punct, rest)) = cursor.punct() {
+        return match punct.as_char() {
+            ',' => true,
+            // `->` and `=>` are parts of expressions
+            '>' => !joint,
+            // A JS property (ie. `.checked=`)
+            '.' => !empty && starts_attribute(rest),
+            '/' => rest
+                .punct()
+                .is_some_and(|(punct, _)| punct.as_char() == '>'),
+            _ => false,
+        };
+    }
+    !empty && starts_attribute(cursor)
+}
+
+/// Checks if the next tokens are a name of an attribute followed by `=`.
+fn starts_attribute(cursor: Cursor) -> bool {
+    let mut rest = match cursor.group(proc_macro2::Delimiter::Bracket) {
+        Some((_, _, rest)) => rest,
+        None => match cursor.ident() {
+            Some((_, mut rest)) => {
+                while let Some((punct, next)) = rest.punct() {
+                    let prefix = punct.as_char() == ':' && punct.spacing() == Spacing::Alone;
+                    if punct.as_char() != '-' && !prefix {
+                        break;
+                    }
+                    match next.ident() {
+                        Some((_, next)) => rest = next,
+                        None => return false,
+                    }
+                }
+                rest
+            }
+            None => return false,
+        },
+    };
+    // Modifiers of a listener
+    while let Some((punct, next)) = rest.punct() {
+        if punct.as_char() != '.' {
+            break;
+        }
+        match next.ident() {
+            Some((_, next)) => rest = next,
+            None => return false,
+        }
+    }
+    match rest.punct() {
+        // `==` and `=>` are parts of expressions, but `=|` starts a closure
+        Some((punct, next)) => {
+            punct.as_char() == '='
+                && (punct.spacing() == Spacing::Alone
+                    || next
+                        .punct()
+                        .is_some_and(|(next, _)| next.as_char() != '=' && next.as_char() != '>'))
+        }
+        None => false,
+    }
+}
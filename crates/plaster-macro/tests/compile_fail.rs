@@ -0,0 +1,5 @@
+#[test]
+fn it_reports_errors_of_templates() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,191 @@
+use plaster::prelude::*;
+use plaster::virtual_dom::{VNode, VRender};
+use plaster_macro::html;
+
+struct Comp;
+
+impl Component for Comp {
+    type Message = ();
+    type Properties = ();
+
+    fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Comp
+    }
+
+    fn update(&mut self, _: Self::Message) -> ShouldRender {
+        unimplemented!();
+    }
+}
+
+impl Renderable<Comp> for Comp {
+    fn view(&self) -> Html<Self> {
+        html! {
+            <ul>
+                <Item label="first" />
+                <Item: label="second", key="second", />
+                <Panel>
+                    <li>{ "inside" }</li>
+                </Panel>
+            </ul>
+        }
+    }
+}
+
+struct Item {
+    label: String,
+}
+
+#[derive(PartialEq, Clone, Default)]
+struct ItemProps {
+    label: String,
+}
+
+impl Component for Item {
+    type Message = ();
+    type Properties = ItemProps;
+
+    fn create(props: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Item { label: props.label }
+    }
+
+    fn update(&mut self, _: Self::Message) -> ShouldRender {
+        unimplemented!();
+    }
+}
+
+impl Renderable<Item> for Item {
+    fn view(&self) -> Html<Self> {
+        html! {
+            <li>{ &self.label }</li>
+        }
+    }
+}
+
+struct Panel {
+    props: PanelProps,
+}
+
+#[derive(PartialEq, Clone, Default)]
+struct PanelProps {
+    children: Children,
+}
+
+impl Component for Panel {
+    type Message = ();
+    type Properties = PanelProps;
+
+    fn create(props: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Panel { props }
+    }
+
+    fn update(&mut self, _: Self::Message) -> ShouldRender {
+        unimplemented!();
+    }
+}
+
+impl Renderable<Panel> for Panel {
+    fn view(&self) -> Html<Self> {
+        html! {
+            <>{ self.props.children.clone() }</>
+        }
+    }
+}
+
+#[test]
+fn it_renders_tags_and_attributes() {
+    let label = "x";
+    let a: VNode<Comp> = html! {
+        <div id="main" class=("b", label) aria-label="Main", data_id=1>
+            <input type="text" value="x" checked=true disabled=false />
+            <p onclick=|_| ()>{ "text" }</p>
+            "raw"
+        </div>
+    };
+
+    assert_eq!(
+        a.render_to_string(),
+        "<div class=\"b x\" aria-label=\"Main\" data_id=\"1\" id=\"main\">\
+         <input type=\"text\" value=\"x\" checked><p>text</p>raw</div>"
+    );
+}
+
+#[test]
+fn it_renders_lists_and_expressions() {
+    let items = ["a", "b"];
+    let a: VNode<Comp> = html! {
+        <ol>
+            { for items.iter().map(|item| html! { <li>{ item }</li> }) }
+            { if items.len() > 1 { "many" } else { "one" } }
+        </ol>
+    };
+
+    assert_eq!(a.render_to_string(), "<ol><li>a</li><li>b</li>many</ol>");
+}
+
+#[test]
+fn it_renders_components() {
+    let html = App::<Comp>::new().render_to_string(None);
+
+    assert_eq!(
+        html,
+        "<ul><li>first</li><li>second</li><li>inside</li></ul>"
+    );
+}
+
+#[test]
+fn it_sets_modifiers_of_listeners() {
+    let a: VNode<Comp> = html! {
+        <form onsubmit.stop.prevent=|_| () onclick=|_| () [custom].capture=|_| ()></form>
+    };
+
+    if let VNode::VTag(vtag) = a {
+        let modifiers: Vec<_> = vtag.listeners.iter().map(|l| l.modifiers()).collect();
+        assert_eq!(
+            modifiers,
+            vec![
+                plaster::html::Modifiers {
+                    stop: true,
+                    prevent: true,
+                    ..Default::default()
+                },
+                Default::default(),
+                plaster::html::Modifiers {
+                    capture: true,
+                    ..Default::default()
+                },
+            ]
+        );
+    } else {
+        panic!("vtag expected");
+    }
+}
+
+#[test]
+fn it_renders_namespaced_attributes() {
+    let a: VNode<Comp> = html! {
+        <svg viewBox="0 0 10 10"><use xlink:href="#icon" /></svg>
+    };
+
+    assert_eq!(
+        a.render_to_string(),
+        "<svg viewBox=\"0 0 10 10\"><use xlink:href=\"#icon\"></use></svg>"
+    );
+}
+
+#[test]
+fn it_sets_properties() {
+    let a: VNode<Comp> = html! {
+        <ion-toggle label="Wi-Fi" .checked=true .disabled=false></ion-toggle>
+    };
+
+    if let VNode::VTag(vtag) = a {
+        assert!(vtag.properties.contains_key("checked"));
+        assert!(vtag.properties.contains_key("disabled"));
+        assert_eq!(
+            vtag.render_to_string(),
+            "<ion-toggle label=\"Wi-Fi\"></ion-toggle>"
+        );
+    } else {
+        panic!("vtag expected");
+    }
+}
@@ -0,0 +1,23 @@
+use plaster::prelude::*;
+use plaster_macro::html;
+
+struct Comp;
+
+impl Component for Comp {
+    type Message = ();
+    type Properties = ();
+
+    fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Comp
+    }
+
+    fn update(&mut self, _: Self::Message) -> ShouldRender {
+        false
+    }
+}
+
+fn main() {
+    let _: Html<Comp> = html! {
+        <div><p>{ "text" }</span></div>
+    };
+}
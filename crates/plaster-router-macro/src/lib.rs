@@ -4,7 +4,7 @@ extern crate quote;
 
 use proc_macro::TokenStream;
 
-#[proc_macro_derive(Routes, attributes(route))]
+#[proc_macro_derive(Routes, attributes(route, query))]
 pub fn plaster_router(input: TokenStream) -> TokenStream {
     match syn::parse2::<syn::Item>(input.into()) {
         Ok(item) => match item {
@@ -19,105 +19,377 @@ pub fn plaster_router(input: TokenStream) -> TokenStream {
 
 fn parse_enum(item: syn::ItemEnum) -> proc_macro2::TokenStream {
     let ident = item.ident;
-    let routes = item.variants.into_iter().map(|variant| {
-        if let Some(path) = parse_route_attr(&variant.attrs) {
-            let mut route = path.as_str();
-            if route.len() != 0 && route.as_bytes()[0] == b'/' {
-                route = &route[1..];
+    let to_path_arms = item
+        .variants
+        .iter()
+        .map(|variant| to_path_arm(&ident, variant))
+        .collect::<Vec<_>>();
+    let routes = item.variants.into_iter().flat_map(|variant| {
+        let paths = parse_route_attrs(&variant.attrs);
+        if paths.is_empty() {
+            panic!("all variants of the enum must have a route attribute");
+        }
+
+        let variant_ident = variant.ident;
+        let fields: Vec<syn::Field> = match variant.fields {
+            syn::Fields::Named(fields) => fields.named.into_iter().collect(),
+            syn::Fields::Unit => Vec::new(),
+            syn::Fields::Unnamed(fields) => {
+                let nested = nested_routes(&ident, &variant_ident, &fields, &paths);
+                return Box::new(nested.into_iter()) as Box<dyn Iterator<Item = _>>;
             }
+        };
+        let ident = ident.clone();
 
-            let route_literal = syn::LitStr::new(route, proc_macro2::Span::call_site());
-            let variant_ident = variant.ident;
-            let mut params = Vec::new();
+        // A variant could have several routes, so `Option` fields could be
+        // filled in by some of them (ie. `/posts` and `/posts/page/:page`)
+        let routes = paths
+            .into_iter()
+            .map(move |path| {
+                let mut route = path.as_str();
+                if route.len() != 0 && route.as_bytes()[0] == b'/' {
+                    route = &route[1..];
+                }
+
+                let route_literal = syn::LitStr::new(route, proc_macro2::Span::call_site());
+                let params = route_params(route);
 
-            for segment in route.split('/') {
-                if segment.len() > 0 && segment.as_bytes()[0] == b':' {
-                    params.push(segment[1..].to_string());
-                } else if segment.len() > 0 && segment.as_bytes()[0] == b'*' {
-                    params.push(segment[1..].to_string());
+                if fields.is_empty() {
+                    if params.len() > 0 {
+                        panic!("all variants with params must have named fields");
+                    }
+                    return quote! {
+                        matcher.add_route(#route_literal, |_, _| Some(#ident::#variant_ident));
+                    };
                 }
-            }
 
-            if params.len() > 0 {
-                if let syn::Fields::Named(fields) = variant.fields {
-                    // todo: make this optional
-                    // let field_names: Vec<String> = fields
-                    //     .named
-                    //     .iter()
-                    //     .map(|field| field.ident.as_ref().unwrap().to_string())
-                    //     .collect();
-
-                    // if params.len() != field_names.len()
-                    //     || params.difference(&field_names).count() > 0
-                    // {
-                    //     panic!("all params must have a field in the variant");
-                    // }
-
-                    let field_idents: Vec<_> = fields
-                        .named
-                        .into_iter()
-                        .map(|field| field.ident.unwrap())
-                        .collect();
-                    let params_literal: Vec<syn::LitStr> = params
-                        .iter()
-                        .map(|param| syn::LitStr::new(param, proc_macro2::Span::call_site()))
-                        .collect();
-
-                    quote! {
-                        router.add_route(#route_literal, |params| {
-                            #ident::#variant_ident {
-                                #(
-                                    #field_idents: params.find(#params_literal).unwrap().to_string()
-                                ),*
+                let values = fields.iter().map(|field| {
+                    let field_ident = field.ident.as_ref().unwrap();
+                    let name = field_ident.to_string();
+                    let name_literal = syn::LitStr::new(&name, proc_macro2::Span::call_site());
+                    let is_param = params.contains(&name);
+
+                    // `#[query]` fields are parsed from the query string the same way
+                    if is_query_field(field) {
+                        if is_param {
+                            panic!(
+                                "field `{}` is both a param of route \"{}\" and a query param",
+                                name, path
+                            );
+                        }
+                        return if option_type(&field.ty).is_some() {
+                            quote! {
+                                #field_ident: match query.get(#name_literal) {
+                                    Some(value) => Some(value.parse().ok()?),
+                                    None => None,
+                                }
+                            }
+                        } else {
+                            quote! {
+                                #field_ident: query.get(#name_literal)?.parse().ok()?
                             }
-                        });
+                        };
                     }
+
+                    // Values which can't be parsed make the route not match
+                    match (option_type(&field.ty).is_some(), is_param) {
+                        (true, true) => quote! {
+                            #field_ident: match params.find(#name_literal) {
+                                Some(value) => Some(plaster_router::url::decode(value).parse().ok()?),
+                                None => None,
+                            }
+                        },
+                        (true, false) => quote! {
+                            #field_ident: None
+                        },
+                        (false, true) => quote! {
+                            #field_ident: plaster_router::url::decode(params.find(#name_literal)?).parse().ok()?
+                        },
+                        (false, false) => panic!(
+                            "field `{}` is not a param of route \"{}\", make it an Option",
+                            name, path
+                        ),
+                    }
+                });
+
+                // Unused closure args are ignored, so generated code has no warnings
+                let params_arg = if params.is_empty() {
+                    quote!(_)
                 } else {
-                    panic!("all variants with params must have named fields");
-                }
-            } else {
+                    quote!(params)
+                };
+                let query_arg = if fields.iter().any(is_query_field) {
+                    quote!(query)
+                } else {
+                    quote!(_)
+                };
                 quote! {
-                    router.add_route(#route_literal, |_| #ident::#variant_ident);
+                    matcher.add_route(#route_literal, |#params_arg, #query_arg| {
+                        Some(#ident::#variant_ident {
+                            #(#values),*
+                        })
+                    });
                 }
-            }
-        } else {
-            panic!("all variants of the enum must have a route attribute");
-        }
+            })
+            .collect::<Vec<_>>();
+        Box::new(routes.into_iter())
     });
 
     quote! {
         impl plaster_router::Routes<#ident> for #ident {
-            fn router(callback: plaster::callback::Callback<()>) -> plaster_router::Router<#ident> {
-                let mut router = plaster_router::Router::new(callback);
+            fn matcher() -> plaster_router::Matcher<#ident> {
+                let mut matcher = plaster_router::Matcher::new();
                 #(#routes)*
-                router
+                matcher
+            }
+        }
+
+        impl #ident {
+            /// Returns the path of the route with the values of fields as params,
+            /// so it could be used in links (ie. `/users/1?tab=posts`).
+            pub fn to_path(&self) -> String {
+                match *self {
+                    #(#to_path_arms)*
+                }
             }
         }
     }
 }
 
-fn parse_route_attr(attrs: &[syn::Attribute]) -> Option<String> {
-    attrs.iter().find_map(|attr| {
-        let meta = attr
-            .parse_meta()
-            .expect("could not parse meta for attribute");
-        match meta {
-            syn::Meta::List(list) => {
-                if list.ident == "route" {
-                    if let Some(route) = list.nested.first() {
-                        if let syn::NestedMeta::Literal(syn::Lit::Str(route)) = route.value() {
-                            Some(route.value())
+/// Generates a match arm of `to_path` for the variant, it fills in params of
+/// the first route which has values for all of them.
+fn to_path_arm(ident: &syn::Ident, variant: &syn::Variant) -> proc_macro2::TokenStream {
+    let variant_ident = &variant.ident;
+    let fields = match variant.fields {
+        syn::Fields::Named(ref fields) => fields.named.iter().collect::<Vec<_>>(),
+        syn::Fields::Unit => Vec::new(),
+        syn::Fields::Unnamed(_) => {
+            let route = parse_route_attrs(&variant.attrs)
+                .into_iter()
+                .next()
+                .expect("all variants of the enum must have a route attribute");
+            let prefix = nested_prefix(&route);
+            return quote! {
+                #ident::#variant_ident(ref nested) => plaster_router::url::with_prefix(#prefix, &nested.to_path()),
+            };
+        }
+    };
+    let field = |name: &str| {
+        fields
+            .iter()
+            .find(|field| field.ident.as_ref().unwrap() == name)
+    };
+
+    // Routes with more params go first, so `Option` fields which are `Some` get into the path
+    let mut paths = parse_route_attrs(&variant.attrs);
+    paths.sort_by_key(|path| std::cmp::Reverse(route_params(path).len()));
+
+    let message = format!(
+        "no route of `{}::{}` has values for all of its params",
+        ident, variant_ident
+    );
+    let mut path = quote!(panic!(#message));
+    for route in paths.iter().rev() {
+        let mut format = String::from("/");
+        let mut args = Vec::new();
+        let mut optional = Vec::new();
+        for (index, segment) in route.trim_start_matches('/').split('/').enumerate() {
+            if index > 0 {
+                format.push('/');
+            }
+            if segment.starts_with(':') || segment.starts_with('*') {
+                let name = &segment[1..];
+                let field = match field(name) {
+                    Some(field) => field,
+                    None => panic!("param `{}` of route \"{}\" is not a field", name, route),
+                };
+                let field_ident = field.ident.as_ref().unwrap();
+                if option_type(&field.ty).is_some() {
+                    optional.push(field_ident);
+                }
+                format.push_str("{}");
+                args.push(if segment.starts_with('*') {
+                    quote!(plaster_router::url::encode_path(&#field_ident.to_string()))
+                } else {
+                    quote!(plaster_router::url::encode(&#field_ident.to_string()))
+                });
+            } else {
+                format.push_str(&segment.replace('{', "{{").replace('}', "}}"));
+            }
+        }
+
+        let value = if args.is_empty() {
+            quote!(String::from(#format))
+        } else {
+            quote!(format!(#format, #(#args),*))
+        };
+        path = if optional.is_empty() {
+            value
+        } else {
+            let bindings = optional.clone();
+            quote! {
+                if let (#(Some(#bindings),)*) = (#(#optional,)*) {
+                    #value
+                } else {
+                    #path
+                }
+            }
+        };
+    }
+
+    let query_fields = fields
+        .iter()
+        .filter(|field| is_query_field(field))
+        .map(|field| {
+            let field_ident = field.ident.as_ref().unwrap();
+            let name_literal =
+                syn::LitStr::new(&field_ident.to_string(), proc_macro2::Span::call_site());
+            if option_type(&field.ty).is_some() {
+                quote! {
+                    if let Some(value) = #field_ident {
+                        query = query.with(#name_literal, value);
+                    }
+                }
+            } else {
+                quote! {
+                    query = query.with(#name_literal, #field_ident);
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+    let value = if query_fields.is_empty() {
+        path
+    } else {
+        quote! {{
+            let path = #path;
+            let mut query = plaster_router::Query::new();
+            #(#query_fields)*
+            plaster_router::url::with_query(&path, &query)
+        }}
+    };
+
+    // Only fields in params or the query are bound, so others aren't unused
+    let bound = fields
+        .iter()
+        .map(|field| field.ident.as_ref().unwrap())
+        .filter(|field_ident| {
+            let name = field_ident.to_string();
+            is_query_field(field(&name).unwrap())
+                || paths
+                    .iter()
+                    .any(|route| route_params(route.trim_start_matches('/')).contains(&name))
+        });
+    match variant.fields {
+        syn::Fields::Named(_) => quote! {
+            #ident::#variant_ident { #(ref #bound,)* .. } => #value,
+        },
+        _ => quote! {
+            #ident::#variant_ident => #value,
+        },
+    }
+}
+
+/// Generates nested routes of a variant with a single field of another `Routes`
+/// enum (ie. `#[route("/admin/*rest")] Admin(AdminRoute)`).
+fn nested_routes(
+    ident: &syn::Ident,
+    variant_ident: &syn::Ident,
+    fields: &syn::FieldsUnnamed,
+    paths: &[String],
+) -> Vec<proc_macro2::TokenStream> {
+    if fields.unnamed.len() != 1 {
+        panic!("variants with nested routes must have a single field");
+    }
+    let ty = &fields.unnamed.first().unwrap().value().ty;
+    paths
+        .iter()
+        .map(|path| {
+            let prefix = nested_prefix(path);
+            quote! {
+                matcher.add_nested(#prefix, <#ty as plaster_router::Routes<#ty>>::matcher(), #ident::#variant_ident);
+            }
+        })
+        .collect()
+}
+
+/// Returns the prefix of a nested route, which has to end with a `*glob`
+/// segment for the rest of the path (ie. `/admin` of `/admin/*rest`).
+fn nested_prefix(route: &str) -> String {
+    match route.rfind('/') {
+        Some(index) if route[index + 1..].starts_with('*') => {
+            let prefix = &route[..index];
+            if !route_params(prefix).is_empty() {
+                panic!("prefix of nested route \"{}\" can't have params", route);
+            }
+            prefix.to_string()
+        }
+        _ => panic!("nested route \"{}\" must end with a *glob segment", route),
+    }
+}
+
+/// Returns names of `:param` and `*glob` segments of a route.
+fn route_params(route: &str) -> Vec<String> {
+    let mut params = Vec::new();
+
+    for segment in route.split('/') {
+        if segment.len() > 0 && segment.as_bytes()[0] == b':' {
+            params.push(segment[1..].to_string());
+        } else if segment.len() > 0 && segment.as_bytes()[0] == b'*' {
+            params.push(segment[1..].to_string());
+        }
+    }
+
+    params
+}
+
+/// Returns `T` if the type is `Option<T>`.
+fn option_type(ty: &syn::Type) -> Option<&syn::Type> {
+    if let syn::Type::Path(ref path) = ty {
+        let segment = path.path.segments.last()?.into_value();
+        if segment.ident == "Option" {
+            if let syn::PathArguments::AngleBracketed(ref args) = segment.arguments {
+                if let Some(syn::GenericArgument::Type(ty)) =
+                    args.args.first().map(|arg| arg.into_value())
+                {
+                    return Some(ty);
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Returns `true` if the field has the `#[query]` attribute.
+fn is_query_field(field: &syn::Field) -> bool {
+    field.attrs.iter().any(|attr| attr.path.is_ident("query"))
+}
+
+fn parse_route_attrs(attrs: &[syn::Attribute]) -> Vec<String> {
+    attrs
+        .iter()
+        .filter_map(|attr| {
+            let meta = attr
+                .parse_meta()
+                .expect("could not parse meta for attribute");
+            match meta {
+                syn::Meta::List(list) => {
+                    if list.ident == "route" {
+                        if let Some(route) = list.nested.first() {
+                            if let syn::NestedMeta::Literal(syn::Lit::Str(route)) = route.value() {
+                                Some(route.value())
+                            } else {
+                                panic!("route spec in route attribute must be a string in quotes");
+                            }
                         } else {
-                            panic!("route spec in route attribute must be a string in quotes");
+                            panic!("must specify a route spec in route attribute");
                         }
                     } else {
-                        panic!("must specify a route spec in route attribute");
+                        None
                     }
-                } else {
-                    None
                 }
+                _ => None,
             }
-            _ => None,
-        }
-    })
+        })
+        .collect()
 }
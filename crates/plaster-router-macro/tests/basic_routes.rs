@@ -1,19 +1,195 @@
+use plaster_router::Routes as _;
 use plaster_router_macro::Routes;
 
 #[test]
 fn test_basic_route() {
-    #[derive(Routes)]
+    #[derive(Routes, Debug, PartialEq)]
     enum BasicRoute {
         #[route("/route1")]
         Route1,
     }
+
+    let matcher = BasicRoute::matcher();
+    assert_eq!(matcher.recognize("/route1"), Some(BasicRoute::Route1));
+    assert_eq!(matcher.recognize("/route2"), None);
 }
 
 #[test]
 fn test_route_with_param() {
-    #[derive(Routes)]
+    #[derive(Routes, Debug, PartialEq)]
     enum BasicRoute {
         #[route("/route1/:param")]
         Route1 { param: String },
     }
+
+    assert_eq!(
+        BasicRoute::matcher().recognize("/route1/a%20b"),
+        Some(BasicRoute::Route1 {
+            param: "a b".to_string()
+        })
+    );
+}
+
+#[test]
+fn test_route_with_typed_params() {
+    #[derive(Routes, Debug, PartialEq)]
+    enum TypedRoute {
+        #[route("/users/:id")]
+        User { id: u64 },
+        #[route("/files/:id")]
+        Upload { id: u64 },
+        #[route("/files/*path")]
+        File { path: String },
+    }
+
+    let matcher = TypedRoute::matcher();
+    assert_eq!(
+        matcher.recognize("/users/5"),
+        Some(TypedRoute::User { id: 5 })
+    );
+    assert_eq!(matcher.recognize("/users/abc"), None);
+    assert_eq!(
+        matcher.recognize("/files/7"),
+        Some(TypedRoute::Upload { id: 7 })
+    );
+    // `:id` is not a number, so the less specific route is tried
+    assert_eq!(
+        matcher.recognize("/files/abc"),
+        Some(TypedRoute::File {
+            path: "abc".to_string()
+        })
+    );
+    assert_eq!(
+        matcher.recognize("/files/a/b"),
+        Some(TypedRoute::File {
+            path: "a/b".to_string()
+        })
+    );
+}
+
+#[test]
+fn test_route_with_optional_param() {
+    #[derive(Routes, Debug, PartialEq)]
+    enum OptionalRoute {
+        #[route("/posts")]
+        #[route("/posts/page/:page")]
+        Posts { page: Option<u32> },
+    }
+
+    let matcher = OptionalRoute::matcher();
+    assert_eq!(
+        matcher.recognize("/posts"),
+        Some(OptionalRoute::Posts { page: None })
+    );
+    assert_eq!(
+        matcher.recognize("/posts/page/2"),
+        Some(OptionalRoute::Posts { page: Some(2) })
+    );
+    assert_eq!(matcher.recognize("/posts/page/last"), None);
+}
+
+#[test]
+fn test_route_with_query_params() {
+    #[derive(Routes, Debug, PartialEq)]
+    enum QueryRoute {
+        #[route("/search")]
+        Search {
+            #[query]
+            q: String,
+            #[query]
+            page: Option<u32>,
+        },
+    }
+
+    let matcher = QueryRoute::matcher();
+    assert_eq!(
+        matcher.recognize("/search?q=a%20b&page=3"),
+        Some(QueryRoute::Search {
+            q: "a b".to_string(),
+            page: Some(3)
+        })
+    );
+    assert_eq!(
+        matcher.recognize("/search?q=rust"),
+        Some(QueryRoute::Search {
+            q: "rust".to_string(),
+            page: None
+        })
+    );
+    // `q` is required
+    assert_eq!(matcher.recognize("/search?page=3"), None);
+}
+
+#[test]
+fn test_route_to_path() {
+    #[derive(Routes)]
+    enum LinkRoute {
+        #[route("/")]
+        Home,
+        #[route("/users/:id")]
+        User { id: u64 },
+        #[route("/posts")]
+        #[route("/posts/page/:page")]
+        Posts {
+            page: Option<u32>,
+            #[query]
+            tag: Option<String>,
+        },
+    }
+
+    assert_eq!(LinkRoute::Home.to_path(), "/");
+    assert_eq!(LinkRoute::User { id: 1 }.to_path(), "/users/1");
+    assert_eq!(
+        LinkRoute::Posts {
+            page: None,
+            tag: None
+        }
+        .to_path(),
+        "/posts"
+    );
+    assert_eq!(
+        LinkRoute::Posts {
+            page: Some(2),
+            tag: Some("a b".to_string())
+        }
+        .to_path(),
+        "/posts/page/2?tag=a%20b"
+    );
+}
+
+#[test]
+fn test_nested_routes() {
+    #[derive(Routes, Debug, PartialEq)]
+    enum AdminRoute {
+        #[route("/")]
+        Dashboard,
+        #[route("/users/:id")]
+        User { id: u64 },
+    }
+
+    #[derive(Routes, Debug, PartialEq)]
+    enum AppRoute {
+        #[route("/")]
+        Home,
+        #[route("/admin/*rest")]
+        Admin(AdminRoute),
+    }
+
+    assert_eq!(AppRoute::Admin(AdminRoute::Dashboard).to_path(), "/admin");
+    assert_eq!(
+        AppRoute::Admin(AdminRoute::User { id: 1 }).to_path(),
+        "/admin/users/1"
+    );
+
+    let matcher = AppRoute::matcher();
+    assert_eq!(matcher.recognize("/"), Some(AppRoute::Home));
+    assert_eq!(
+        matcher.recognize("/admin"),
+        Some(AppRoute::Admin(AdminRoute::Dashboard))
+    );
+    assert_eq!(
+        matcher.recognize("/admin/users/5?x=1"),
+        Some(AppRoute::Admin(AdminRoute::User { id: 5 }))
+    );
+    assert_eq!(matcher.recognize("/admin/users/abc"), None);
 }
@@ -0,0 +1,88 @@
+use std::cell::Cell;
+use wasm_bindgen::JsValue;
+use web_sys::window;
+
+/// Where routers read the current route from and write it to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum History {
+    /// The path of the URL with `history.pushState`, the server has to serve
+    /// the app for all of its routes.
+    Browser,
+    /// The fragment of the URL (ie. `/#/posts/1`), for static file hosts
+    /// which can't fall back to `index.html` for unknown paths.
+    Hash,
+    /// Only in memory, for apps without an address bar (ie. mobile apps).
+    Memory,
+}
+
+thread_local! {
+    static HISTORY: Cell<History> = const {
+        Cell::new(if cfg!(feature = "mobile") {
+            History::Memory
+        } else {
+            History::Browser
+        })
+    };
+}
+
+/// Sets the history of routers and `route_to`, it has to be called before
+/// routers are created. It's `History::Memory` with the `mobile` feature
+/// and `History::Browser` otherwise.
+pub fn set_history(history: History) {
+    HISTORY.with(|cell| cell.set(history));
+}
+
+/// Returns the history set with `set_history`.
+pub fn history() -> History {
+    HISTORY.with(|cell| cell.get())
+}
+
+impl History {
+    /// Returns the route of the current location.
+    pub(crate) fn current_route(self) -> String {
+        let location = window().expect("need a window context").location();
+        match self {
+            History::Browser => format!(
+                "{}{}{}",
+                location.pathname().unwrap_or("/".to_string()),
+                location.search().unwrap_or_default(),
+                location.hash().unwrap_or_default()
+            ),
+            History::Hash => {
+                let hash = location.hash().unwrap_or_default();
+                let route = hash.trim_start_matches('#');
+                if route.starts_with('/') {
+                    route.to_string()
+                } else {
+                    format!("/{}", route)
+                }
+            }
+            History::Memory => "/".to_string(),
+        }
+    }
+
+    /// Pushes the route to the history of the browser, it doesn't fire
+    /// `popstate` or `hashchange`.
+    pub(crate) fn push(self, route: &str) {
+        let url = match self {
+            History::Browser => route.to_string(),
+            History::Hash => format!("#{}", route),
+            History::Memory => return,
+        };
+        window()
+            .expect("need a window context")
+            .history()
+            .expect("history API unavailable")
+            .push_state_with_url(&JsValue::NULL, "", Some(&url))
+            .expect("could not pushState");
+    }
+
+    /// Returns the event which is fired when the user goes back or forward.
+    pub(crate) fn change_event(self) -> Option<&'static str> {
+        match self {
+            History::Browser => Some("popstate"),
+            History::Hash => Some("hashchange"),
+            History::Memory => None,
+        }
+    }
+}
@@ -1,31 +1,42 @@
 use js_sys::Function;
 use plaster::callback::Callback;
-use route_recognizer::{Params, Router as RecRouter};
+use route_recognizer::Params;
 use serde_derive::{Deserialize, Serialize};
 use std::sync::{Arc, Mutex};
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::{JsCast, JsValue};
-use web_sys::{window, CustomEvent, CustomEventInit};
+use web_sys::{window, CustomEvent, CustomEventInit, Event};
 
 use log::trace;
 pub use plaster_router_macro::Routes;
 
+mod history;
+mod matcher;
+pub mod url;
+
+pub use crate::history::{history, set_history, History};
+pub use crate::matcher::Matcher;
+pub use crate::url::Query;
+
 pub struct Router<T> {
-    routes: Vec<fn(Params) -> T>,
-    index_router: RecRouter<usize>,
+    matcher: Matcher<T>,
     current_path: Arc<Mutex<String>>,
     listener: Closure<dyn FnMut(CustomEvent)>,
+    history: History,
+    history_listener: Closure<dyn FnMut(Event)>,
     callback: Callback<()>,
 }
 
 impl<T> Router<T> {
     pub fn new(callback: Callback<()>) -> Router<T> {
+        Router::with_matcher(callback, Matcher::new())
+    }
+
+    /// Creates a router with the routes of the matcher.
+    pub fn with_matcher(callback: Callback<()>, matcher: Matcher<T>) -> Router<T> {
         let win = window().expect("need a window context");
-        let path = if cfg!(not(feature = "mobile")) {
-            win.location().pathname().unwrap_or("/".to_string())
-        } else {
-            "/".to_string()
-        };
+        let history = history::history();
+        let path = history.current_route();
         trace!("initial route: {}", &path);
         let current_path = Arc::new(Mutex::new(path));
         let current_path_c = current_path.clone();
@@ -46,92 +57,107 @@ impl<T> Router<T> {
         win.add_event_listener_with_callback("plasterroutechange", listener_function)
             .expect("could not attach global event listener");
 
-        if cfg!(not(feature = "mobile")) {
-            win.add_event_listener_with_callback("popstate", listener_function)
-                .expect("could not attach popstate event listener");
+        // Going back or forward changes the location, so the route is read from it
+        let current_path_c = current_path.clone();
+        let callback_c = callback.clone();
+        let history_listener = Closure::wrap(Box::new(move |_: Event| {
+            let route = history.current_route();
+            trace!("route change: {}", &route);
+            *current_path_c.lock().unwrap() = route;
+            callback_c.emit(());
+        }) as Box<dyn FnMut(_)>);
+
+        if let Some(event) = history.change_event() {
+            win.add_event_listener_with_callback(event, history_listener.as_ref().unchecked_ref())
+                .expect("could not attach history event listener");
         }
 
         Router {
-            routes: Vec::new(),
-            index_router: RecRouter::new(),
-            current_path: current_path,
+            matcher,
+            current_path,
             listener: listener_callback,
-            callback: callback,
+            history,
+            history_listener,
+            callback,
         }
     }
 
-    pub fn add_route(&mut self, route: &str, closure: fn(Params) -> T) {
-        trace!("added route: {}", route);
-        let index = self.routes.len();
-        self.routes.push(closure);
-        self.index_router.add(route, index);
+    /// Adds a route, see `Matcher::add_route`.
+    pub fn add_route(&mut self, route: &str, closure: fn(Params, &Query) -> Option<T>) {
+        self.matcher.add_route(route, closure);
+    }
+
+    /// Adds nested routes under the prefix, see `Matcher::add_nested`.
+    pub fn add_nested<U>(&mut self, prefix: &str, nested: Matcher<U>, wrap: fn(U) -> T)
+    where
+        T: 'static,
+        U: 'static,
+    {
+        self.matcher.add_nested(prefix, nested, wrap);
     }
 
     pub fn navigate(&mut self, path: &str) {
         *self.current_path.lock().unwrap() = path.to_string();
-        if cfg!(not(feature = "mobile")) {
-            self.push_state();
-        }
+        self.history.push(path);
         self.callback.emit(());
     }
 
     pub fn resolve(&self) -> Option<T> {
-        let route_match = self
-            .index_router
-            .recognize(&self.current_path.lock().unwrap())
-            .ok();
-        route_match.map(|m| self.routes.get(m.handler.clone()).unwrap()(m.params))
+        self.matcher.recognize(&self.current_path.lock().unwrap())
     }
 
     pub fn current_route(&self) -> String {
         self.current_path.lock().unwrap().clone()
     }
 
-    pub fn set_route(&self, path: &str) {
-        *self.current_path.lock().unwrap() = path.to_string();
+    /// Returns the parsed query of the current route.
+    pub fn current_query(&self) -> Query {
+        Query::parse(url::split(&self.current_path.lock().unwrap()).1)
     }
 
-    fn push_state(&self) {
-        match window().expect("need a window context").history() {
-            Ok(history) => {
-                history
-                    .push_state_with_url(
-                        &JsValue::NULL,
-                        "",
-                        Some(&self.current_path.lock().unwrap()),
-                    )
-                    .expect("could not pushState");
-            }
-            Err(_) => (),
-        }
+    /// Returns the fragment of the current route, without the `#`.
+    pub fn current_fragment(&self) -> Option<String> {
+        url::split(&self.current_path.lock().unwrap())
+            .2
+            .map(|fragment| fragment.to_string())
+    }
+
+    pub fn set_route(&self, path: &str) {
+        *self.current_path.lock().unwrap() = path.to_string();
     }
 }
 
 impl<T> Drop for Router<T> {
     fn drop(&mut self) {
-        window()
-            .expect("need window context")
-            .remove_event_listener_with_callback(
-                "plasterroutechange",
-                self.listener.as_ref().unchecked_ref(),
+        let win = window().expect("need window context");
+        win.remove_event_listener_with_callback(
+            "plasterroutechange",
+            self.listener.as_ref().unchecked_ref(),
+        )
+        .expect("could not remove event listener");
+        if let Some(event) = self.history.change_event() {
+            win.remove_event_listener_with_callback(
+                event,
+                self.history_listener.as_ref().unchecked_ref(),
             )
-            .expect("could not remove event listener");
+            .expect("could not remove history event listener");
+        }
     }
 }
 
 pub trait Routes<T> {
-    fn router(callback: Callback<()>) -> Router<T>;
+    /// Returns the routes, to resolve paths without a window or to nest them.
+    fn matcher() -> Matcher<T>;
+
+    fn router(callback: Callback<()>) -> Router<T> {
+        Router::with_matcher(callback, Self::matcher())
+    }
 }
 
 pub fn route_to(path: &str) {
     let win = window().expect("need window context");
 
-    if cfg!(not(feature = "mobile")) {
-        win.history()
-            .expect("history API unavailable")
-            .push_state_with_url(&JsValue::NULL, "", Some(path))
-            .expect("could not pushState");
-    }
+    history::history().push(path);
 
     let mut init = CustomEventInit::new();
     init.detail(
@@ -146,6 +172,11 @@ pub fn route_to(path: &str) {
         .expect("could not dispatch route change");
 }
 
+/// Routes to the path with the query (ie. `/posts?page=2&sort=name`).
+pub fn route_to_with_query(path: &str, query: &Query) {
+    route_to(&url::with_query(path, query));
+}
+
 #[derive(Serialize, Deserialize)]
 struct RouteEvent {
     route: String,
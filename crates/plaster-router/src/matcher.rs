@@ -0,0 +1,130 @@
+use crate::url::{self, Query};
+use log::trace;
+use route_recognizer::{Params, Router as RecRouter};
+use std::rc::Rc;
+
+/// A route of nested routes, which captures them.
+type Nested<T> = Box<dyn Fn(Params, &Query) -> Option<T>>;
+
+/// A handler of a route, which returns the value for params of the path.
+enum Handler<T> {
+    Route(fn(Params, &Query) -> Option<T>),
+    Nested(Nested<T>),
+}
+
+/// A route with its own recognizer, so the next matching route could be tried
+/// when the handler of a better one rejects the params.
+struct Route<T> {
+    recognizer: RecRouter<()>,
+    rank: Rank,
+    handler: Handler<T>,
+}
+
+/// Numbers of star, dynamic and static segments of a route, lower ranks are
+/// tried first like `route_recognizer` prefers them.
+type Rank = (usize, usize, usize);
+
+fn rank(route: &str) -> Rank {
+    let route = route.trim_start_matches('/');
+    route
+        .split('/')
+        .fold((0, 0, 0), |(stars, dynamics, statics), segment| {
+            if segment.starts_with('*') {
+                (stars + 1, dynamics, statics)
+            } else if segment.starts_with(':') {
+                (stars, dynamics + 1, statics)
+            } else {
+                (stars, dynamics, statics + 1)
+            }
+        })
+}
+
+/// Routes which match paths to values of `T`, without a window or history,
+/// so routes of one enum could be nested into routes of another one.
+pub struct Matcher<T> {
+    /// Routes ordered by rank, routes of the same rank in the order they were added.
+    routes: Vec<Route<T>>,
+}
+
+impl<T> Matcher<T> {
+    pub fn new() -> Matcher<T> {
+        Matcher { routes: Vec::new() }
+    }
+
+    /// Adds a route, the closure gets params of the path and the query and
+    /// returns `None` if they don't fit the route (ie. `:id` is not a number),
+    /// so the next matching route is tried.
+    pub fn add_route(&mut self, route: &str, closure: fn(Params, &Query) -> Option<T>) {
+        trace!("added route: {}", route);
+        self.add(route, Handler::Route(closure));
+    }
+
+    /// Adds nested routes under the prefix (ie. `admin`), the rest of the path
+    /// is matched by the nested routes and the value is wrapped with `wrap`.
+    pub fn add_nested<U>(&mut self, prefix: &str, nested: Matcher<U>, wrap: fn(U) -> T)
+    where
+        T: 'static,
+        U: 'static,
+    {
+        let prefix = prefix.trim_matches('/');
+        trace!("added nested routes: {}", prefix);
+        let nested = Rc::new(nested);
+        let routes = if prefix.is_empty() {
+            vec![String::new(), "*rest".to_string()]
+        } else {
+            vec![prefix.to_string(), format!("{}/*rest", prefix)]
+        };
+        for route in routes {
+            let nested = nested.clone();
+            self.add(
+                &route,
+                Handler::Nested(Box::new(move |params: Params, query: &Query| {
+                    let rest = params.find("rest").unwrap_or("");
+                    nested.resolve(rest, query).map(wrap)
+                })),
+            );
+        }
+    }
+
+    /// Returns the value of the route which matches the URL, only the path is
+    /// matched and the query is passed to the route.
+    pub fn recognize(&self, url: &str) -> Option<T> {
+        let (path, query, _) = url::split(url);
+        self.resolve(path, &Query::parse(query))
+    }
+
+    fn add(&mut self, route: &str, handler: Handler<T>) {
+        let rank = rank(route);
+        let mut recognizer = RecRouter::new();
+        recognizer.add(route, ());
+        let index = self
+            .routes
+            .iter()
+            .position(|other| other.rank > rank)
+            .unwrap_or(self.routes.len());
+        self.routes.insert(
+            index,
+            Route {
+                recognizer,
+                rank,
+                handler,
+            },
+        );
+    }
+
+    fn resolve(&self, path: &str, query: &Query) -> Option<T> {
+        self.routes.iter().find_map(|route| {
+            let route_match = route.recognizer.recognize(path).ok()?;
+            match route.handler {
+                Handler::Route(closure) => closure(route_match.params, query),
+                Handler::Nested(ref closure) => closure(route_match.params, query),
+            }
+        })
+    }
+}
+
+impl<T> Default for Matcher<T> {
+    fn default() -> Matcher<T> {
+        Matcher::new()
+    }
+}
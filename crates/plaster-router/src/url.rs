@@ -0,0 +1,164 @@
+//! Splitting of URLs, query strings and percent-encoding.
+
+use std::fmt;
+
+/// Splits a URL into the path, the query string and the fragment
+/// (ie. `/posts?page=2#top` into `/posts`, `page=2` and `top`).
+pub fn split(url: &str) -> (&str, &str, Option<&str>) {
+    let (rest, fragment) = match url.find('#') {
+        Some(index) => (&url[..index], Some(&url[index + 1..])),
+        None => (url, None),
+    };
+    match rest.find('?') {
+        Some(index) => (&rest[..index], &rest[index + 1..], fragment),
+        None => (rest, "", fragment),
+    }
+}
+
+/// Percent-encodes everything except unreserved characters of RFC 3986,
+/// so the value could be used as a segment of a path or a part of a query.
+pub fn encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Percent-encodes segments of a path, but keeps `/` between them
+/// (ie. the value of a `*glob` param).
+pub fn encode_path(value: &str) -> String {
+    value.split('/').map(encode).collect::<Vec<_>>().join("/")
+}
+
+/// Decodes percent-encoded bytes, invalid escapes are kept as they are.
+pub fn decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' && index + 2 < bytes.len() {
+            let hex = &bytes[index + 1..index + 3];
+            let byte = std::str::from_utf8(hex)
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+            if let Some(byte) = byte {
+                decoded.push(byte);
+                index += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[index]);
+        index += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Appends the query to the path, if it isn't empty
+/// (ie. `/posts` and `page=2` into `/posts?page=2`).
+pub fn with_query(path: &str, query: &Query) -> String {
+    if query.is_empty() {
+        path.to_string()
+    } else {
+        format!("{}?{}", path, query)
+    }
+}
+
+/// Prepends the prefix of nested routes to the path of a nested route
+/// (ie. `/admin` and `/users?page=2` into `/admin/users?page=2`).
+pub fn with_prefix(prefix: &str, path: &str) -> String {
+    let prefix = prefix.trim_end_matches('/');
+    let path = path.trim_start_matches('/');
+    if path.is_empty() || path.starts_with('?') || path.starts_with('#') {
+        format!("{}{}", if prefix.is_empty() { "/" } else { prefix }, path)
+    } else {
+        format!("{}/{}", prefix, path)
+    }
+}
+
+/// Parsed params of a query string (ie. `page=2&sort=name`).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Query {
+    pairs: Vec<(String, String)>,
+}
+
+impl Query {
+    /// Creates an empty query.
+    pub fn new() -> Query {
+        Query::default()
+    }
+
+    /// Parses a query string with or without the leading `?`.
+    pub fn parse(query: &str) -> Query {
+        let query = query.strip_prefix('?').unwrap_or(query);
+        let pairs = query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (name, value) = match pair.find('=') {
+                    Some(index) => (&pair[..index], &pair[index + 1..]),
+                    None => (pair, ""),
+                };
+                // Forms encode spaces as `+`
+                (
+                    decode(&name.replace('+', " ")),
+                    decode(&value.replace('+', " ")),
+                )
+            })
+            .collect();
+        Query { pairs }
+    }
+
+    /// Adds a param and returns the query, to build URLs for navigation.
+    pub fn with<V: ToString>(mut self, name: &str, value: V) -> Query {
+        self.pairs.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Returns the first value of the param.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.pairs
+            .iter()
+            .find(|(other, _)| other == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns all values of the param (ie. `tag=a&tag=b`).
+    pub fn get_all(&self, name: &str) -> Vec<&str> {
+        self.pairs
+            .iter()
+            .filter(|(other, _)| other == name)
+            .map(|(_, value)| value.as_str())
+            .collect()
+    }
+
+    /// Returns `true` if there are no params.
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Iterates over names and values of params in order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.pairs
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+}
+
+impl fmt::Display for Query {
+    /// Writes the encoded query string without the leading `?`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, (name, value)) in self.iter().enumerate() {
+            if index > 0 {
+                f.write_str("&")?;
+            }
+            write!(f, "{}={}", encode(name), encode(value))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,20 @@
+use plaster::callback::Callback;
+use plaster_router::{set_history, History, Router};
+use wasm_bindgen_test::*;
+use web_sys::window;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+fn hash_history_reads_and_writes_the_fragment() {
+    set_history(History::Hash);
+    let location = window().unwrap().location();
+    location.set_hash("/posts/2?page=1").unwrap();
+
+    let mut router: Router<()> = Router::new(Callback::from(|_| ()));
+    assert_eq!(router.current_route(), "/posts/2?page=1");
+
+    router.navigate("/users/1");
+    assert_eq!(location.hash().unwrap(), "#/users/1");
+    assert_eq!(router.current_route(), "/users/1");
+}
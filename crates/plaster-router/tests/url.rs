@@ -0,0 +1,53 @@
+use plaster_router::url::{decode, encode, encode_path, split, with_prefix, with_query, Query};
+
+#[test]
+fn it_splits_urls() {
+    assert_eq!(split("/posts"), ("/posts", "", None));
+    assert_eq!(
+        split("/posts?page=2#top"),
+        ("/posts", "page=2", Some("top"))
+    );
+    assert_eq!(split("/posts#a?b"), ("/posts", "", Some("a?b")));
+}
+
+#[test]
+fn it_parses_queries() {
+    let query = Query::parse("?page=2&sort=first+name&tag=a&tag=b%26c&flag");
+    assert_eq!(query.get("page"), Some("2"));
+    assert_eq!(query.get("sort"), Some("first name"));
+    assert_eq!(query.get_all("tag"), vec!["a", "b&c"]);
+    assert_eq!(query.get("flag"), Some(""));
+    assert_eq!(query.get("missing"), None);
+}
+
+#[test]
+fn it_builds_urls_with_queries() {
+    let query = Query::new().with("q", "a b&c").with("page", 2);
+    assert_eq!(query.to_string(), "q=a%20b%26c&page=2");
+    assert_eq!(Query::parse(&query.to_string()), query);
+    assert_eq!(with_query("/search", &query), "/search?q=a%20b%26c&page=2");
+    assert_eq!(with_query("/search", &Query::new()), "/search");
+}
+
+#[test]
+fn it_keeps_invalid_escapes() {
+    assert_eq!(decode("100%"), "100%");
+    assert_eq!(decode("%zz%41"), "%zzA");
+    assert_eq!(decode("caf%C3%A9"), "café");
+}
+
+#[test]
+fn it_encodes_paths() {
+    assert_eq!(encode("a/b c"), "a%2Fb%20c");
+    assert_eq!(encode_path("docs/a b.md"), "docs/a%20b.md");
+    assert_eq!(decode(&encode("ünïcode?&=")), "ünïcode?&=");
+}
+
+#[test]
+fn it_prefixes_nested_paths() {
+    assert_eq!(with_prefix("/admin", "/"), "/admin");
+    assert_eq!(with_prefix("/admin", "/users/1"), "/admin/users/1");
+    assert_eq!(with_prefix("/admin", "/?tab=all"), "/admin?tab=all");
+    assert_eq!(with_prefix("", "/users"), "/users");
+    assert_eq!(with_prefix("", "/"), "/");
+}
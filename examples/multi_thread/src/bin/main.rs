@@ -1,13 +1,16 @@
-extern crate web_logger;
-extern crate yew;
+extern crate console_log;
 extern crate multi_thread;
+extern crate plaster;
+extern crate wasm_bindgen;
 
-use yew::prelude::*;
 use multi_thread::Model;
+use plaster::prelude::*;
+use wasm_bindgen::prelude::*;
 
-fn main() {
-    web_logger::init();
-    yew::initialize();
+#[wasm_bindgen(start)]
+pub fn start() {
+    console_log::init();
     App::<Model>::new().mount_to_body();
-    yew::run_loop();
 }
+
+fn main() {}
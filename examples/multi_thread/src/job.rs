@@ -1,61 +1,35 @@
-use std::time::Duration;
-use yew::prelude::worker::*;
-// TODO use yew::services::{IntervalService, FetchService, Task};
-use yew::services::Task;
-use yew::services::interval::IntervalService;
-use yew::services::fetch::FetchService;
+use plaster::prelude::worker::*;
+use serde_derive::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub enum Request {
     GetDataFromServer,
 }
 
-impl Transferable for Request { }
+impl Transferable for Request {}
 
 #[derive(Serialize, Deserialize, Debug)]
 pub enum Response {
     DataFetched,
 }
 
-impl Transferable for Response { }
-
-pub enum Msg {
-    Updating,
-}
+impl Transferable for Response {}
 
 pub struct Worker {
     link: AgentLink<Worker>,
-    interval: IntervalService,
-    task: Box<Task>,
-    fetch: FetchService,
 }
 
 impl Agent for Worker {
     type Reach = Job;
-    type Message = Msg;
+    type Message = ();
     type Input = Request;
     type Output = Response;
 
     fn create(link: AgentLink<Self>) -> Self {
-        let mut interval = IntervalService::new();
-        let duration = Duration::from_secs(3);
-        let callback = link.send_back(|_| Msg::Updating);
-        let task = interval.spawn(duration, callback);
-        Worker {
-            link,
-            interval,
-            task: Box::new(task),
-            fetch: FetchService::new(),
-        }
+        Worker { link }
     }
 
-    fn update(&mut self, msg: Self::Message) {
-        match msg {
-            Msg::Updating => {
-                info!("Tick...");
-            }
-        }
-    }
+    fn update(&mut self, _: Self::Message) {}
 
     fn handle(&mut self, msg: Self::Input, who: HandlerId) {
         info!("Request: {:?}", msg);
@@ -1,21 +1,19 @@
 #[macro_use]
 extern crate log;
 #[macro_use]
-extern crate serde_derive;
-#[macro_use]
-extern crate yew;
+extern crate plaster;
 
-pub mod native_worker;
-pub mod job;
 pub mod context;
+pub mod job;
+pub mod native_worker;
 
-use yew::prelude::*;
+use plaster::prelude::*;
 
 pub struct Model {
-    worker: Box<Bridge<native_worker::Worker>>,
-    job: Box<Bridge<job::Worker>>,
-    context: Box<Bridge<context::Worker>>,
-    context_2: Box<Bridge<context::Worker>>,
+    worker: Box<dyn Bridge<native_worker::Worker>>,
+    job: Box<dyn Bridge<job::Worker>>,
+    context: Box<dyn Bridge<context::Worker>>,
+    context_2: Box<dyn Bridge<context::Worker>>,
 }
 
 pub enum Msg {
@@ -29,7 +27,7 @@ impl Component for Model {
     type Message = Msg;
     type Properties = ();
 
-    fn create(_: Self::Properties, mut link: ComponentLink<Self>) -> Self {
+    fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
         let callback = link.send_back(|_| Msg::DataReceived);
         let worker = native_worker::Worker::bridge(callback);
 
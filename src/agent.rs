@@ -1,15 +1,18 @@
 //! This module contains types to support multi-threading in Yew.
 
-use std::rc::Rc;
-use std::cell::RefCell;
-use std::marker::PhantomData;
-use serde::{Serialize, Deserialize};
-use bincode;
 use anymap::{AnyMap, Entry};
-use slab::Slab;
-use stdweb::Value;
-use scheduler::{Runnable, scheduler};
+use bincode;
 use callback::Callback;
+use js_sys::{global, Uint8Array};
+use scheduler::{scheduler, Runnable};
+use serde::{Deserialize, Serialize};
+use slab::Slab;
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{DedicatedWorkerGlobalScope, MessageEvent, Worker};
 use Shared;
 
 #[derive(Serialize, Deserialize)]
@@ -20,11 +23,7 @@ enum ToWorker<T> {
     Destroy,
 }
 
-impl<T> Transferable for ToWorker<T>
-where
-    T: Serialize + for <'de> Deserialize<'de>,
-{
-}
+impl<T> Transferable for ToWorker<T> where T: Serialize + for<'de> Deserialize<'de> {}
 
 #[derive(Serialize, Deserialize)]
 enum FromWorker<T> {
@@ -33,37 +32,45 @@ enum FromWorker<T> {
     ProcessOutput(HandlerId, T),
 }
 
-impl<T> Transferable for FromWorker<T>
-where
-    T: Serialize + for <'de> Deserialize<'de>,
-{
-}
-
+impl<T> Transferable for FromWorker<T> where T: Serialize + for<'de> Deserialize<'de> {}
 
 /// Represents a message which you could send to an agent.
 pub trait Transferable
 where
-    Self: Serialize + for <'de> Deserialize<'de>,
+    Self: Serialize + for<'de> Deserialize<'de>,
 {
 }
 
 trait Packed {
     fn pack(&self) -> Vec<u8>;
-    fn unpack(data: &Vec<u8>) -> Self;
+    fn unpack(data: &[u8]) -> Self;
 }
 
 impl<T: Transferable> Packed for T {
     fn pack(&self) -> Vec<u8> {
-        bincode::serialize(&self)
-            .expect("can't serialize a transferable object")
+        bincode::serialize(&self).expect("can't serialize a transferable object")
     }
 
-    fn unpack(data: &Vec<u8>) -> Self {
-        bincode::deserialize(&data)
-            .expect("can't deserialize a transferable object")
+    fn unpack(data: &[u8]) -> Self {
+        bincode::deserialize(data).expect("can't deserialize a transferable object")
     }
 }
 
+/// Converts packed data to a value which could be posted to a worker.
+fn to_message(data: &[u8]) -> JsValue {
+    Uint8Array::from(data).into()
+}
+
+/// Extracts packed data from an event of a worker.
+fn from_message(event: &MessageEvent) -> Vec<u8> {
+    Uint8Array::new(&event.data()).to_vec()
+}
+
+/// Returns the scope of the worker the code runs in.
+fn worker_scope() -> DedicatedWorkerGlobalScope {
+    global().unchecked_into()
+}
+
 /// Id of responses handler.
 #[derive(Serialize, Deserialize, Eq, PartialEq, Hash, Clone, Copy)]
 pub struct HandlerId(usize);
@@ -95,48 +102,46 @@ pub trait Threaded {
 
 impl<T> Threaded for T
 where
-    T: Agent<Reach=Public>,
+    T: Agent,
 {
     fn register() {
         let scope = AgentScope::<T>::new();
-        let responder = WorkerResponder { };
+        let responder = WorkerResponder {};
         let link = AgentLink::connect(&scope, responder);
         let upd = AgentUpdate::Create(link);
         scope.send(upd);
-        let handler = move |data: Vec<u8>| {
-            let msg = ToWorker::<T::Input>::unpack(&data);
+        let handler = move |event: MessageEvent| {
+            let msg = ToWorker::<T::Input>::unpack(&from_message(&event));
             match msg {
                 ToWorker::Connected(id) => {
                     let upd = AgentUpdate::Connected(id);
                     scope.send(upd);
-                },
+                }
                 ToWorker::ProcessInput(id, value) => {
                     let upd = AgentUpdate::Input(value, id);
                     scope.send(upd);
-                },
+                }
                 ToWorker::Disconnected(id) => {
                     let upd = AgentUpdate::Disconnected(id);
                     scope.send(upd);
-                },
+                }
                 ToWorker::Destroy => {
                     let upd = AgentUpdate::Destroy;
                     scope.send(upd);
-                    js! {
-                        // Terminates web worker
-                        self.close();
-                    };
-                },
+                    // Terminates web worker
+                    worker_scope().close();
+                }
             }
         };
+        let handler = Closure::wrap(Box::new(handler) as Box<dyn FnMut(MessageEvent)>);
+        let worker = worker_scope();
+        worker.set_onmessage(Some(handler.as_ref().unchecked_ref()));
+        // The handler lives as long as the worker
+        handler.forget();
         let loaded: FromWorker<T::Output> = FromWorker::WorkerLoaded;
-        let loaded = loaded.pack();
-        js! {
-            var handler = @{handler};
-            self.onmessage = function(event) {
-                handler(event.data);
-            };
-            self.postMessage(@{loaded});
-        };
+        worker
+            .post_message(&to_message(&loaded.pack()))
+            .expect("can't notify that the worker is loaded");
     }
 }
 
@@ -216,14 +221,16 @@ impl Discoverer for Context {
                 Entry::Occupied(mut entry) => {
                     // TODO Insert callback!
                     entry.get_mut().create_bridge(callback)
-                },
+                }
                 Entry::Vacant(entry) => {
                     let scope = AgentScope::<AGN>::new();
                     let launched = LocalAgent::new(&scope);
-                    let responder = SlabResponder { slab: launched.slab() };
+                    let responder = SlabResponder {
+                        slab: launched.slab(),
+                    };
                     scope_to_init = Some((scope.clone(), responder));
                     entry.insert(launched).create_bridge(callback)
-                },
+                }
             }
         });
         if let Some((scope, responder)) = scope_to_init {
@@ -231,7 +238,7 @@ impl Discoverer for Context {
             let upd = AgentUpdate::Create(agent_link);
             scope.send(upd);
         }
-        let upd = AgentUpdate::Connected(bridge.id.into());
+        let upd = AgentUpdate::Connected(bridge.id);
         bridge.scope.send(upd);
         Box::new(bridge)
     }
@@ -337,33 +344,86 @@ impl<AGN: Agent> Drop for JobBridge<AGN> {
 
 // <<< SEPARATE THREAD >>>
 
+/// A spawned web worker. Messages are queued until the agent in the worker
+/// reports that it's loaded, because the worker would drop them otherwise.
+#[derive(Clone)]
+struct RemoteWorker {
+    worker: Worker,
+    pending: Shared<Option<Vec<Vec<u8>>>>,
+    _handler: Rc<Closure<dyn FnMut(MessageEvent)>>,
+}
+
+impl RemoteWorker {
+    /// Spawns a worker from the resource and sends outputs of its agent to the handler.
+    fn spawn<OUT, F>(name_of_resource: &str, handler: F) -> Self
+    where
+        OUT: Transferable,
+        F: Fn(HandlerId, OUT) + 'static,
+    {
+        let worker = Worker::new(name_of_resource).expect("can't spawn a worker");
+        let pending: Shared<Option<Vec<Vec<u8>>>> = Rc::new(RefCell::new(Some(Vec::new())));
+        let handler = {
+            let worker = worker.clone();
+            let pending = pending.clone();
+            move |event: MessageEvent| {
+                let msg = FromWorker::<OUT>::unpack(&from_message(&event));
+                match msg {
+                    FromWorker::WorkerLoaded => {
+                        let queue = pending.borrow_mut().take().unwrap_or_default();
+                        for data in queue {
+                            worker
+                                .post_message(&to_message(&data))
+                                .expect("can't post a message to the worker");
+                        }
+                    }
+                    FromWorker::ProcessOutput(id, output) => {
+                        handler(id, output);
+                    }
+                }
+            }
+        };
+        let handler = Closure::wrap(Box::new(handler) as Box<dyn FnMut(MessageEvent)>);
+        worker.set_onmessage(Some(handler.as_ref().unchecked_ref()));
+        RemoteWorker {
+            worker,
+            pending,
+            _handler: Rc::new(handler),
+        }
+    }
+
+    fn send<IN: Transferable>(&self, msg: ToWorker<IN>) {
+        let data = msg.pack();
+        if let Some(ref mut queue) = *self.pending.borrow_mut() {
+            queue.push(data);
+            return;
+        }
+        self.worker
+            .post_message(&to_message(&data))
+            .expect("can't post a message to the worker");
+    }
+
+    /// Stops handling of outputs, because the agent is not used anymore.
+    /// A worker which hasn't loaded yet would never get the queued messages
+    /// (`Destroy` among them), so it's terminated instead.
+    fn detach(&self) {
+        self.worker.set_onmessage(None);
+        if self.pending.borrow().is_some() {
+            self.worker.terminate();
+        }
+    }
+}
+
 /// Create a new instance for every bridge.
 pub struct Private;
 
 impl Discoverer for Private {
     fn spawn_or_join<AGN: Agent>(callback: Callback<AGN::Output>) -> Box<dyn Bridge<AGN>> {
-        let handler = move |data: Vec<u8>| {
-            let msg = FromWorker::<AGN::Output>::unpack(&data);
-            match msg {
-                FromWorker::WorkerLoaded => {
-                    // TODO Send `Connected` message
-                },
-                FromWorker::ProcessOutput(id, output) => {
-                    assert_eq!(id.raw_id(), SINGLETON_ID.raw_id());
-                    callback.emit(output);
-                },
-            }
-        };
-        // TODO Need somethig better...
-        let name_of_resource = AGN::name_of_resource();
-        let worker = js! {
-            var worker = new Worker(@{name_of_resource});
-            var handler = @{handler};
-            worker.onmessage = function(event) {
-                handler(event.data);
-            };
-            return worker;
+        let handler = move |id: HandlerId, output| {
+            assert_eq!(id.raw_id(), SINGLETON_ID.raw_id());
+            callback.emit(output);
         };
+        let worker = RemoteWorker::spawn(AGN::name_of_resource(), handler);
+        worker.send(ToWorker::<AGN::Input>::Connected(SINGLETON_ID));
         let bridge = PrivateBridge {
             worker,
             _agent: PhantomData,
@@ -374,38 +434,32 @@ impl Discoverer for Private {
 
 /// A connection manager for components interaction with workers.
 pub struct PrivateBridge<T: Agent> {
-    worker: Value,
+    worker: RemoteWorker,
     _agent: PhantomData<T>,
 }
 
 impl<AGN: Agent> Bridge<AGN> for PrivateBridge<AGN> {
     fn send(&mut self, msg: AGN::Input) {
-        // TODO Important! Implement.
-        // Use a queue to collect a messages if an instance is not ready
-        // and send them to an agent when it will reported readiness.
-        let msg = ToWorker::ProcessInput(SINGLETON_ID, msg).pack();
-        let worker = &self.worker;
-        js! {
-            var worker = @{worker};
-            var bytes = @{msg};
-            worker.postMessage(bytes);
-        };
+        self.worker.send(ToWorker::ProcessInput(SINGLETON_ID, msg));
     }
 }
 
 impl<AGN: Agent> Drop for PrivateBridge<AGN> {
     fn drop(&mut self) {
-        // TODO Send `Destroy` message.
+        self.worker
+            .send(ToWorker::<AGN::Input>::Disconnected(SINGLETON_ID));
+        self.worker.send(ToWorker::<AGN::Input>::Destroy);
+        self.worker.detach();
     }
 }
 
 struct RemoteAgent<AGN: Agent> {
-    worker: Value,
+    worker: RemoteWorker,
     slab: Shared<Slab<Callback<AGN::Output>>>,
 }
 
 impl<AGN: Agent> RemoteAgent<AGN> {
-    pub fn new(worker: &Value, slab: Shared<Slab<Callback<AGN::Output>>>) -> Self {
+    pub fn new(worker: &RemoteWorker, slab: Shared<Slab<Callback<AGN::Output>>>) -> Self {
         RemoteAgent {
             worker: worker.clone(),
             slab,
@@ -437,70 +491,44 @@ pub struct Public;
 
 impl Discoverer for Public {
     fn spawn_or_join<AGN: Agent>(callback: Callback<AGN::Output>) -> Box<dyn Bridge<AGN>> {
-        let bridge = REMOTE_AGENTS_POOL.with(|pool| {
-            match pool.borrow_mut().entry::<RemoteAgent<AGN>>() {
-                Entry::Occupied(mut entry) => {
-                    // TODO Insert callback!
-                    entry.get_mut().create_bridge(callback)
-                },
+        let bridge =
+            REMOTE_AGENTS_POOL.with(|pool| match pool.borrow_mut().entry::<RemoteAgent<AGN>>() {
+                Entry::Occupied(mut entry) => entry.get_mut().create_bridge(callback),
                 Entry::Vacant(entry) => {
                     let slab_base: Shared<Slab<Callback<AGN::Output>>> =
                         Rc::new(RefCell::new(Slab::new()));
                     let slab = slab_base.clone();
-                    let handler = move |data: Vec<u8>| {
-                        let msg = FromWorker::<AGN::Output>::unpack(&data);
-                        match msg {
-                            FromWorker::WorkerLoaded => {
-                                // TODO Use `AtomicBool` lock to check its loaded
-                                // TODO Send `Connected` message
-                            },
-                            FromWorker::ProcessOutput(id, output) => {
-                                let callback = slab.borrow().get(id.raw_id()).cloned();
-                                if let Some(callback) = callback {
-                                    callback.emit(output);
-                                } else {
-                                    warn!("Id of handler for remote worker not exists <slab>: {}", id.raw_id());
-                                }
-                            },
+                    let handler = move |id: HandlerId, output| {
+                        let callback = slab.borrow().get(id.raw_id()).cloned();
+                        if let Some(callback) = callback {
+                            callback.emit(output);
+                        } else {
+                            warn!(
+                                "Id of handler for remote worker not exists <slab>: {}",
+                                id.raw_id()
+                            );
                         }
                     };
-                    let name_of_resource = AGN::name_of_resource();
-                    let worker = js! {
-                        var worker = new Worker(@{name_of_resource});
-                        var handler = @{handler};
-                        worker.onmessage = function(event) {
-                            handler(event.data);
-                        };
-                        return worker;
-                    };
+                    let worker = RemoteWorker::spawn(AGN::name_of_resource(), handler);
                     let launched = RemoteAgent::new(&worker, slab_base);
                     entry.insert(launched).create_bridge(callback)
-                },
-            }
-        });
+                }
+            });
+        bridge.send_to_remote(ToWorker::Connected(bridge.id));
         Box::new(bridge)
     }
 }
 
 /// A connection manager for components interaction with workers.
 pub struct PublicBridge<T: Agent> {
-    worker: Value,
+    worker: RemoteWorker,
     id: HandlerId,
     _agent: PhantomData<T>,
 }
 
 impl<AGN: Agent> PublicBridge<AGN> {
     fn send_to_remote(&self, msg: ToWorker<AGN::Input>) {
-        // TODO Important! Implement.
-        // Use a queue to collect a messages if an instance is not ready
-        // and send them to an agent when it will reported readiness.
-        let msg = msg.pack();
-        let worker = &self.worker;
-        js! {
-            var worker = @{worker};
-            var bytes = @{msg};
-            worker.postMessage(bytes);
-        };
+        self.worker.send(msg);
     }
 }
 
@@ -526,17 +554,17 @@ impl<AGN: Agent> Drop for PublicBridge<AGN> {
             if terminate_worker {
                 let upd = ToWorker::Destroy;
                 self.send_to_remote(upd);
+                self.worker.detach();
                 pool.borrow_mut().remove::<RemoteAgent<AGN>>();
             }
         });
     }
 }
 
-
 /// Create a single instance in a browser.
 pub struct Global;
 
-impl Discoverer for Global { }
+impl Discoverer for Global {}
 
 /// Declares the behavior of the agent.
 pub trait Agent: Sized + 'static {
@@ -556,22 +584,22 @@ pub trait Agent: Sized + 'static {
     fn update(&mut self, msg: Self::Message);
 
     /// This method called on when a new bridge created.
-    fn connected(&mut self, _id: HandlerId) { }
+    fn connected(&mut self, _id: HandlerId) {}
 
     /// This method called on every incoming message.
     fn handle(&mut self, msg: Self::Input, id: HandlerId);
 
     /// This method called on when a new bridge destroyed.
-    fn disconnected(&mut self, _id: HandlerId) { }
+    fn disconnected(&mut self, _id: HandlerId) {}
 
     /// Creates an instance of an agent.
-    fn destroy(&mut self) { }
+    fn destroy(&mut self) {}
 
     /// Represents the name of loading resorce for remote workers which
     /// have to live in a separate files.
-    fn name_of_resource() -> &'static str { "main.js" }
-
-
+    fn name_of_resource() -> &'static str {
+        "main.js"
+    }
 }
 
 /// This sctruct holds a reference to a component and to a global scheduler.
@@ -607,17 +635,15 @@ trait Responder<AGN: Agent> {
     fn response(&self, id: HandlerId, output: AGN::Output);
 }
 
-struct WorkerResponder {
-}
+struct WorkerResponder {}
 
 impl<AGN: Agent> Responder<AGN> for WorkerResponder {
     fn response(&self, id: HandlerId, output: AGN::Output) {
         let msg = FromWorker::ProcessOutput(id, output);
         let data = msg.pack();
-        js! {
-            var data = @{data};
-            self.postMessage(data);
-        };
+        worker_scope()
+            .post_message(&to_message(&data))
+            .expect("can't post a response from the worker");
     }
 }
 
@@ -703,27 +729,33 @@ where
                 this.agent = Some(AGN::create(env));
             }
             AgentUpdate::Message(msg) => {
-                this.agent.as_mut()
+                this.agent
+                    .as_mut()
                     .expect("agent was not created to process messages")
                     .update(msg);
             }
             AgentUpdate::Connected(id) => {
-                this.agent.as_mut()
+                this.agent
+                    .as_mut()
                     .expect("agent was not created to send a connected message")
                     .connected(id);
             }
             AgentUpdate::Input(inp, id) => {
-                this.agent.as_mut()
+                this.agent
+                    .as_mut()
                     .expect("agent was not created to process inputs")
                     .handle(inp, id);
             }
             AgentUpdate::Disconnected(id) => {
-                this.agent.as_mut()
+                this.agent
+                    .as_mut()
                     .expect("agent was not created to send a disconnected message")
                     .disconnected(id);
             }
             AgentUpdate::Destroy => {
-                let mut agent = this.agent.take()
+                let mut agent = this
+                    .agent
+                    .take()
                     .expect("trying to destroy not existent agent");
                 agent.destroy();
             }
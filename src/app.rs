@@ -1,13 +1,20 @@
 //! This module contains `App` sctruct which used to bootstrap
 //! a component in an isolated scope.
 
-use html::{Component, Renderable, Scope};
-use web_sys::{window, Element};
+use delegation::Delegator;
+use html::{Component, NodeCell, Renderable, Scope};
+use std::cell::RefCell;
+use std::rc::Rc;
+use web_sys::{window, Element, Node};
 
 /// An application instance.
 pub struct App<COMP: Component> {
     /// `Scope` holder
     scope: Scope<COMP>,
+    /// Handle events with one listener per event type on the mount element.
+    delegate_events: bool,
+    /// Postpone renders caused by messages until the next animation frame.
+    batch_renders: bool,
 }
 
 impl<COMP> App<COMP>
@@ -17,7 +24,27 @@ where
     /// Creates a new `App` with a component in a context.
     pub fn new() -> Self {
         let scope = Scope::new();
-        App { scope }
+        App {
+            scope,
+            delegate_events: false,
+            batch_renders: false,
+        }
+    }
+
+    /// Handles events of all elements with one listener per event type on the mount element
+    /// instead of a listener per element. Events which don't bubble are still listened
+    /// on elements.
+    pub fn delegate_events(mut self) -> Self {
+        self.delegate_events = true;
+        self
+    }
+
+    /// Postpones renders caused by messages until the next animation frame, so a component
+    /// which receives many messages in a row renders once per frame. Parents are rendered
+    /// before their children. Other apps mounted to the page keep rendering right away.
+    pub fn batch_renders(mut self) -> Self {
+        self.batch_renders = true;
+        self
     }
 
     /// Alias to `mount("body", ...)`.
@@ -56,7 +83,60 @@ where
     /// will render the model to a virtual DOM tree.
     pub fn mount(self, element: Element, props: Option<COMP::Properties>) -> Scope<COMP> {
         clear_element(&element);
-        self.scope.mount_in_place(element, None, None, props)
+        self.scope_for(&element)
+            .mount_in_place(element, None, None, props)
+    }
+
+    /// Mounts the component over the markup which is already in the element (for example
+    /// rendered on a server with `render_to_string`). The first render adopts the existing
+    /// DOM nodes and attaches listeners to them instead of creating new ones.
+    pub fn hydrate(self, element: Element, props: Option<COMP::Properties>) -> Scope<COMP> {
+        let node = element.first_child();
+        let occupied: NodeCell = Rc::new(RefCell::new(None));
+        let scope = self.scope_for(&element).hydrate_in_place(
+            element.clone(),
+            node,
+            Some(occupied.clone()),
+            props,
+        );
+        // The root is hydrated right away, whatever it didn't claim was rendered in excess
+        let last = occupied.borrow().clone();
+        remove_after(&element, last);
+        scope
+    }
+
+    /// Renders the component to a string of HTML markup instead of mounting it.
+    /// It doesn't need a browser, so it can be used to prerender pages on a server.
+    pub fn render_to_string(self, props: Option<COMP::Properties>) -> String {
+        self.scope.render_to_string(props)
+    }
+
+    /// Returns the scope for a component mounted to the element.
+    fn scope_for(self, element: &Element) -> Scope<COMP> {
+        let delegator = if self.delegate_events {
+            Some(Delegator::new(element))
+        } else {
+            None
+        };
+        self.scope
+            .with_delegator(delegator)
+            .with_batching(self.batch_renders)
+    }
+}
+
+/// Removes children of the element which follow the `last` one, or all of them.
+fn remove_after(element: &Element, last: Option<Node>) {
+    let mut excess = match last {
+        Some(node) => node.next_sibling(),
+        None => element.first_child(),
+    };
+    while let Some(node) = excess {
+        excess = node.next_sibling();
+        warn!(
+            "hydration mismatch: removed {} after the root",
+            node.node_name()
+        );
+        element.remove_child(&node).expect("can't remove a child");
     }
 }
 
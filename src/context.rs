@@ -0,0 +1,189 @@
+//! This module contains contexts which components provide to their subtrees.
+//! Every scope has a context linked to the context of the parent component,
+//! so descendants could find the nearest provided value of a type.
+
+use anymap::AnyMap;
+use std::cell::RefCell;
+use std::ptr;
+use std::rc::{Rc, Weak};
+
+/// A callback of a component which is notified about new values of a context.
+pub(crate) type Subscriber<T> = Rc<dyn Fn(&T)>;
+
+/// A subscriber which doesn't keep the callback alive.
+type WeakSubscriber<T> = Weak<dyn Fn(&T)>;
+
+/// A provided value.
+struct Provided<T> {
+    value: T,
+}
+
+/// Weak references to subscribers of a type in the subtree of a context, with the
+/// contexts of their components to find out which provider they listen to.
+type Listeners<T> = Vec<(Weak<Context>, WeakSubscriber<T>)>;
+
+/// Values provided by a component.
+pub(crate) struct Context {
+    parent: Option<Rc<Context>>,
+    values: RefCell<AnyMap>,
+    listeners: RefCell<AnyMap>,
+}
+
+impl Context {
+    /// Creates an empty context which inherits values from the `parent` context.
+    pub(crate) fn new(parent: Option<Rc<Context>>) -> Rc<Context> {
+        Rc::new(Context {
+            parent,
+            values: RefCell::new(AnyMap::new()),
+            listeners: RefCell::new(AnyMap::new()),
+        })
+    }
+
+    /// Provides the value to descendants and notifies subscribers if it has changed.
+    /// Subscribers which get the value from a closer provider aren't notified.
+    pub(crate) fn provide<T>(&self, value: T)
+    where
+        T: Clone + PartialEq + 'static,
+    {
+        {
+            let mut values = self.values.borrow_mut();
+            match values.get_mut::<Provided<T>>() {
+                Some(provided) => {
+                    if provided.value == value {
+                        return;
+                    }
+                    provided.value = value.clone();
+                }
+                None => {
+                    values.insert(Provided {
+                        value: value.clone(),
+                    });
+                }
+            }
+        }
+        let subscribers: Vec<_> = {
+            let mut listeners = self.listeners.borrow_mut();
+            let listeners = match listeners.get_mut::<Listeners<T>>() {
+                Some(listeners) => listeners,
+                None => return,
+            };
+            listeners.retain(is_alive);
+            listeners
+                .iter()
+                .filter_map(|(context, subscriber)| {
+                    let context = context.upgrade()?;
+                    let provider = context.provider::<T>()?;
+                    if ptr::eq(provider, self) {
+                        subscriber.upgrade()
+                    } else {
+                        None
+                    }
+                })
+                .collect()
+        };
+        // Values are not borrowed here, because subscribers could provide values too
+        for subscriber in subscribers {
+            subscriber(&value);
+        }
+    }
+
+    /// Returns the value provided by the nearest ancestor.
+    pub(crate) fn inherited<T: Clone + 'static>(&self) -> Option<T> {
+        self.provider::<T>().map(|provider| {
+            provider
+                .values
+                .borrow()
+                .get::<Provided<T>>()
+                .expect("provider lost the value")
+                .value
+                .clone()
+        })
+    }
+
+    /// Subscribes the `context` of a component to changes of the value provided by
+    /// the nearest ancestor and returns its current value. Ancestors which don't
+    /// provide the value yet notify the subscriber when they do. The subscription
+    /// lasts while the subscriber is alive.
+    pub(crate) fn subscribe<T: Clone + 'static>(
+        context: &Rc<Context>,
+        subscriber: &Subscriber<T>,
+    ) -> Option<T> {
+        let mut ancestor = context.parent.as_ref();
+        while let Some(current) = ancestor {
+            let mut listeners = current.listeners.borrow_mut();
+            let listeners = listeners.entry::<Listeners<T>>().or_insert_with(Vec::new);
+            listeners.retain(is_alive);
+            listeners.push((Rc::downgrade(context), Rc::downgrade(subscriber)));
+            ancestor = current.parent.as_ref();
+        }
+        context.inherited()
+    }
+
+    /// Finds the nearest ancestor context which provides a value of the type.
+    fn provider<T: 'static>(&self) -> Option<&Context> {
+        let mut context = self.parent.as_ref();
+        while let Some(current) = context {
+            if current.values.borrow().contains::<Provided<T>>() {
+                return Some(current);
+            }
+            context = current.parent.as_ref();
+        }
+        None
+    }
+}
+
+/// Returns `true` if the component of a listener and its subscriber are still there.
+fn is_alive<T>(listener: &(Weak<Context>, WeakSubscriber<T>)) -> bool {
+    listener.0.strong_count() > 0 && listener.1.strong_count() > 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    /// Returns a subscriber which counts its calls and remembers the last value.
+    fn counter(calls: &Rc<Cell<usize>>, last: &Rc<Cell<u32>>) -> Subscriber<u32> {
+        let calls = calls.clone();
+        let last = last.clone();
+        Rc::new(move |value: &u32| {
+            calls.set(calls.get() + 1);
+            last.set(*value);
+        })
+    }
+
+    #[test]
+    fn it_notifies_subscribers_about_values_provided_later() {
+        let root = Context::new(None);
+        let child = Context::new(Some(root.clone()));
+        let (calls, last) = (Rc::new(Cell::new(0)), Rc::new(Cell::new(0)));
+        let subscriber = counter(&calls, &last);
+
+        assert_eq!(Context::subscribe(&child, &subscriber), None);
+        root.provide(1u32);
+        assert_eq!((calls.get(), last.get()), (1, 1));
+        root.provide(1u32);
+        assert_eq!(calls.get(), 1);
+
+        drop(subscriber);
+        root.provide(2u32);
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn it_notifies_subscribers_of_the_nearest_provider_only() {
+        let root = Context::new(None);
+        let middle = Context::new(Some(root.clone()));
+        let child = Context::new(Some(middle.clone()));
+        let (calls, last) = (Rc::new(Cell::new(0)), Rc::new(Cell::new(0)));
+        let subscriber = counter(&calls, &last);
+        root.provide(1u32);
+
+        assert_eq!(Context::subscribe(&child, &subscriber), Some(1));
+        middle.provide(2u32);
+        assert_eq!((calls.get(), last.get()), (1, 2));
+        root.provide(3u32);
+        assert_eq!((calls.get(), last.get()), (1, 2));
+        assert_eq!(child.inherited::<u32>(), Some(2));
+    }
+}
@@ -0,0 +1,206 @@
+//! This module contains a delegator which handles DOM events of an app
+//! with one listener per event type on the mount element.
+
+use html::{EventHandler, EventListenerHandle, Modifiers};
+use js_sys::Reflect;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::{Rc, Weak};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, Event, Node};
+use Shared;
+
+/// Name of the property which keeps the id of an element with delegated handlers.
+const ID_PROPERTY: &str = "__plaster_id";
+
+/// Events which don't bubble, so their listeners are attached to elements directly.
+const NON_BUBBLING: &[&str] = &[
+    "abort",
+    "blur",
+    "canplay",
+    "canplaythrough",
+    "durationchange",
+    "emptied",
+    "ended",
+    "error",
+    "focus",
+    "invalid",
+    "load",
+    "loadeddata",
+    "loadedmetadata",
+    "loadstart",
+    "mouseenter",
+    "mouseleave",
+    "pause",
+    "play",
+    "playing",
+    "pointerenter",
+    "pointerleave",
+    "progress",
+    "ratechange",
+    "scroll",
+    "seeked",
+    "seeking",
+    "stalled",
+    "suspend",
+    "timeupdate",
+    "toggle",
+    "volumechange",
+    "waiting",
+];
+
+/// Counter of ids for elements with delegated handlers.
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Handlers of delegated events by event type and element id.
+type Handlers = HashMap<String, HashMap<usize, Vec<Shared<EventHandler>>>>;
+
+struct Registry {
+    root: Element,
+    listeners: HashMap<String, Closure<dyn FnMut(Event)>>,
+    handlers: Handlers,
+}
+
+impl Drop for Registry {
+    fn drop(&mut self) {
+        for (event_type, closure) in self.listeners.drain() {
+            self.root
+                .remove_event_listener_with_callback(&event_type, closure.as_ref().unchecked_ref())
+                .expect("could not remove event listener");
+        }
+    }
+}
+
+/// Dispatches DOM events which bubble to the mount element to handlers of nested elements.
+#[derive(Clone)]
+pub(crate) struct Delegator {
+    registry: Shared<Registry>,
+}
+
+impl Delegator {
+    /// Creates a delegator which listens to events on the `root` element.
+    pub(crate) fn new(root: &Element) -> Self {
+        let registry = Registry {
+            root: root.clone(),
+            listeners: HashMap::new(),
+            handlers: HashMap::new(),
+        };
+        Delegator {
+            registry: Rc::new(RefCell::new(registry)),
+        }
+    }
+
+    /// Returns `true` if events of the type could be delegated.
+    pub(crate) fn delegates(&self, event_type: &str) -> bool {
+        !NON_BUBBLING.contains(&event_type)
+    }
+
+    /// Registers a handler of events of the element and returns a handle which
+    /// unregisters it when removed or dropped.
+    pub(crate) fn register(
+        &self,
+        element: &Element,
+        handler: EventHandler,
+        event_type: &str,
+        modifiers: Modifiers,
+    ) -> EventListenerHandle {
+        let id = element_id(element).unwrap_or_else(|| {
+            let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+            Reflect::set(element, &ID_PROPERTY.into(), &(id as f64).into())
+                .expect("could not set id of the element");
+            id
+        });
+        let handler = Rc::new(RefCell::new(handler));
+        {
+            let mut registry = self.registry.borrow_mut();
+            if !registry.listeners.contains_key(event_type) {
+                let weak = Rc::downgrade(&self.registry);
+                let closure =
+                    Closure::wrap(Box::new(move |event: Event| dispatch(&weak, event))
+                        as Box<dyn FnMut(Event)>);
+                registry
+                    .root
+                    .add_event_listener_with_callback(event_type, closure.as_ref().unchecked_ref())
+                    .expect("could not add event listener to the root element");
+                trace!("add_delegated_listener: {}", event_type);
+                registry.listeners.insert(event_type.to_owned(), closure);
+            }
+            registry
+                .handlers
+                .entry(event_type.to_owned())
+                .or_default()
+                .entry(id)
+                .or_default()
+                .push(handler.clone());
+        }
+        EventListenerHandle::delegated(self.clone(), id, handler, event_type, modifiers)
+    }
+
+    /// Removes the handler of events of the element with the id.
+    pub(crate) fn unregister(&self, id: usize, handler: &Shared<EventHandler>, event_type: &str) {
+        let mut registry = self.registry.borrow_mut();
+        if let Some(by_id) = registry.handlers.get_mut(event_type) {
+            let empty = match by_id.get_mut(&id) {
+                Some(handlers) => {
+                    handlers.retain(|other| !Rc::ptr_eq(other, handler));
+                    handlers.is_empty()
+                }
+                None => false,
+            };
+            if empty {
+                by_id.remove(&id);
+            }
+        }
+    }
+}
+
+/// Reads the id of an element with delegated handlers.
+fn element_id(node: &Node) -> Option<usize> {
+    Reflect::get(node, &ID_PROPERTY.into())
+        .ok()
+        .and_then(|id| id.as_f64())
+        .map(|id| id as usize)
+}
+
+/// Calls handlers from the target of the event up to the root element until
+/// one of them stops propagation of the event.
+fn dispatch(registry: &Weak<RefCell<Registry>>, event: Event) {
+    let registry = match registry.upgrade() {
+        Some(registry) => registry,
+        None => return,
+    };
+    let root: Node = registry.borrow().root.clone().into();
+    let event_type = event.type_();
+    let mut node = event
+        .target()
+        .and_then(|target| target.dyn_into::<Node>().ok());
+    while let Some(current) = node {
+        if current.is_same_node(Some(&root)) {
+            break;
+        }
+        if let Some(id) = element_id(&current) {
+            // Handlers are cloned, because they could render and change the registry
+            let handlers = registry
+                .borrow()
+                .handlers
+                .get(&event_type)
+                .and_then(|by_id| by_id.get(&id))
+                .map(|handlers| {
+                    handlers
+                        .iter()
+                        .map(|handler| handler.borrow().clone())
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            for handler in handlers {
+                handler(event.clone());
+            }
+            if event.cancel_bubble() {
+                break;
+            }
+        }
+        node = current.parent_node();
+    }
+}
@@ -0,0 +1,124 @@
+//! This module contains a minimal executor which polls `std` futures
+//! from the microtask queue of the browser.
+
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+use std::mem;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::window;
+
+/// A spawned future which is polled every time its waker is woken.
+pub(crate) struct Task {
+    future: RefCell<Option<Pin<Box<dyn Future<Output = ()>>>>>,
+    queued: Cell<bool>,
+    cancelled: Cell<bool>,
+}
+
+impl Task {
+    /// Polls the future for the first time right away and returns the task
+    /// which could be used to cancel it later. The callback gets the output of the future.
+    pub(crate) fn spawn<F, C>(future: F, callback: C) -> Rc<Task>
+    where
+        F: Future + 'static,
+        C: FnOnce(F::Output) + 'static,
+    {
+        let future = Then {
+            future: Box::pin(future),
+            callback: Some(Box::new(callback)),
+        };
+        let task = Rc::new(Task {
+            future: RefCell::new(Some(Box::pin(future))),
+            queued: Cell::new(false),
+            cancelled: Cell::new(false),
+        });
+        Task::run(task.clone());
+        task
+    }
+
+    /// Drops the future, so it will never be polled again.
+    pub(crate) fn cancel(&self) {
+        self.cancelled.set(true);
+        self.future.borrow_mut().take();
+    }
+
+    fn run(task: Rc<Task>) {
+        task.queued.set(false);
+        // The future is taken out of the cell, because it could cancel its own task
+        let mut future = match task.future.borrow_mut().take() {
+            Some(future) => future,
+            None => return,
+        };
+        let waker = waker(task.clone());
+        let mut context = Context::from_waker(&waker);
+        if future.as_mut().poll(&mut context).is_pending() && !task.cancelled.get() {
+            *task.future.borrow_mut() = Some(future);
+        }
+    }
+
+    fn schedule(task: Rc<Task>) {
+        if task.queued.replace(true) {
+            return;
+        }
+        let callback = Closure::once_into_js(move || Task::run(task));
+        window()
+            .expect("futures need a window context")
+            .queue_microtask(callback.unchecked_ref());
+    }
+}
+
+/// A future which passes the output of another future to a callback.
+struct Then<F: Future> {
+    future: Pin<Box<F>>,
+    callback: Option<Box<dyn FnOnce(F::Output)>>,
+}
+
+impl<F: Future> Future for Then<F> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<()> {
+        match self.future.as_mut().poll(context) {
+            Poll::Ready(output) => {
+                let callback = self
+                    .callback
+                    .take()
+                    .expect("future polled after completion");
+                callback(output);
+                Poll::Ready(())
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+static VTABLE: RawWakerVTable = RawWakerVTable::new(clone_waker, wake, wake_by_ref, drop_waker);
+
+// Wasm is single-threaded, so wakers could hold the task with `Rc`.
+fn waker(task: Rc<Task>) -> Waker {
+    let raw = RawWaker::new(Rc::into_raw(task) as *const (), &VTABLE);
+    unsafe { Waker::from_raw(raw) }
+}
+
+unsafe fn clone_waker(data: *const ()) -> RawWaker {
+    let task = Rc::from_raw(data as *const Task);
+    let cloned = task.clone();
+    mem::forget(task);
+    RawWaker::new(Rc::into_raw(cloned) as *const (), &VTABLE)
+}
+
+unsafe fn wake(data: *const ()) {
+    Task::schedule(Rc::from_raw(data as *const Task));
+}
+
+unsafe fn wake_by_ref(data: *const ()) {
+    let task = Rc::from_raw(data as *const Task);
+    Task::schedule(task.clone());
+    mem::forget(task);
+}
+
+unsafe fn drop_waker(data: *const ()) {
+    drop(Rc::from_raw(data as *const Task));
+}
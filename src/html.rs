@@ -3,51 +3,197 @@
 //! Also this module contains declaration of `Component` trait which used
 //! to create own UI-components.
 
+use anymap::AnyMap;
 use callback::Callback;
+use context::{Context, Subscriber};
+use delegation::Delegator;
+use executor::Task;
 use futures::Future;
 use scheduler::{scheduler, Runnable};
 use std::cell::RefCell;
-use std::rc::Rc;
-use virtual_dom::{Listener, VDiff, VNode};
+use std::fmt;
+use std::mem;
+use std::rc::{Rc, Weak};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use virtual_dom::{Listener, VDiff, VNode, VRender};
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::future_to_promise;
-use web_sys::{window, Element, EventTarget, HtmlSelectElement, Node};
+use web_sys::{window, AddEventListenerOptions, Element, EventTarget, HtmlSelectElement, Node};
 use Shared;
 
-/// A handle to an event listener
+/// A Rust handler of DOM events which could be swapped under an attached listener.
+pub type EventHandler = Rc<dyn Fn(web_sys::Event)>;
+
+/// Modifiers of an event listener, set in templates after the name of the event
+/// (ie. `onclick.stop.prevent=|_| Msg::Submit`). Listeners without modifiers let
+/// events propagate and keep their default actions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Modifiers {
+    /// Stops propagation of the event to listeners of ancestors.
+    pub stop: bool,
+    /// Prevents the default action of the event.
+    pub prevent: bool,
+    /// Handles the event in the capturing phase, before listeners of descendants.
+    pub capture: bool,
+    /// Promises the browser that the listener never prevents the default action.
+    pub passive: bool,
+    /// Removes the listener after the first event.
+    pub once: bool,
+}
+
+impl Modifiers {
+    /// Returns `true` if the listener could be handled by the delegator of the app,
+    /// which listens in the bubbling phase with ordinary options.
+    pub(crate) fn delegable(&self) -> bool {
+        !self.capture && !self.passive && !self.once
+    }
+
+    /// Wraps the handler to stop propagation or prevent the default action first.
+    pub(crate) fn wrap(&self, handler: EventHandler) -> EventHandler {
+        if !self.stop && !self.prevent {
+            return handler;
+        }
+        if self.prevent && self.passive {
+            warn!("the default action of a passive listener can't be prevented");
+        }
+        let modifiers = *self;
+        Rc::new(move |event: web_sys::Event| {
+            if modifiers.stop {
+                event.stop_propagation();
+            }
+            if modifiers.prevent {
+                event.prevent_default();
+            }
+            handler(event);
+        })
+    }
+}
+
+/// Where the handler of a listener is called from.
+enum ListenerTarget {
+    /// The DOM listener is attached to the element itself.
+    Direct {
+        event_target: EventTarget,
+        closure: Closure<dyn FnMut(web_sys::Event)>,
+    },
+    /// The delegator of the app calls the handler for an element with the id.
+    Delegated { delegator: Delegator, id: usize },
+}
+
+/// A handle to an event listener. The listener is removed when the handle is dropped.
+#[must_use = "the event listener is removed when the handle is dropped"]
 pub struct EventListenerHandle {
-    event_target: EventTarget,
-    closure: Closure<dyn FnMut(web_sys::Event)>,
+    target: ListenerTarget,
+    handler: Shared<EventHandler>,
     type_: String,
+    modifiers: Modifiers,
 }
 
 impl EventListenerHandle {
-    /// Create a new EventListenerHandle with the target Element, the converted Closure, and the
-    /// event type (ie. "onclick").
-    pub fn new(
+    /// Create a new EventListenerHandle with the target Element, the handler, and the
+    /// event type (ie. "click").
+    pub fn new(target: &EventTarget, handler: EventHandler, type_: &str) -> EventListenerHandle {
+        EventListenerHandle::with_modifiers(target, handler, type_, Modifiers::default())
+    }
+
+    /// Create a new EventListenerHandle with options of the DOM listener taken
+    /// from the modifiers. The handler is expected to be wrapped by the modifiers already.
+    pub fn with_modifiers(
         target: &EventTarget,
-        closure: Closure<dyn FnMut(web_sys::Event)>,
+        handler: EventHandler,
         type_: &str,
+        modifiers: Modifiers,
     ) -> EventListenerHandle {
+        let handler = Rc::new(RefCell::new(handler));
+        let current = handler.clone();
+        let closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+            // Clone the handler out of the cell, because it could be replaced while it runs
+            let handler = current.borrow().clone();
+            handler(event);
+        }) as Box<dyn FnMut(web_sys::Event)>);
+
+        let options = AddEventListenerOptions::new();
+        options.set_capture(modifiers.capture);
+        options.set_passive(modifiers.passive);
+        options.set_once(modifiers.once);
         target
-            .add_event_listener_with_callback(type_, closure.as_ref().unchecked_ref())
+            .add_event_listener_with_callback_and_add_event_listener_options(
+                type_,
+                closure.as_ref().unchecked_ref(),
+                &options,
+            )
             .expect("could not add event listener to element");
 
         trace!("add_event_listener: {}", type_);
 
         EventListenerHandle {
-            event_target: target.clone(),
-            closure: closure,
+            target: ListenerTarget::Direct {
+                event_target: target.clone(),
+                closure,
+            },
+            handler,
             type_: type_.to_string(),
+            modifiers,
         }
     }
 
+    /// Create a handle to a handler registered in the delegator.
+    pub(crate) fn delegated(
+        delegator: Delegator,
+        id: usize,
+        handler: Shared<EventHandler>,
+        type_: &str,
+        modifiers: Modifiers,
+    ) -> EventListenerHandle {
+        EventListenerHandle {
+            target: ListenerTarget::Delegated { delegator, id },
+            handler,
+            type_: type_.to_string(),
+            modifiers,
+        }
+    }
+
+    /// Returns the event type the listener is attached for.
+    pub fn type_(&self) -> &str {
+        &self.type_
+    }
+
+    /// Returns the modifiers the listener is attached with.
+    pub fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    /// Replaces the handler of events without touching the DOM listener.
+    pub fn set_handler(&self, handler: EventHandler) {
+        *self.handler.borrow_mut() = handler;
+    }
+
     /// Remove the event listener from the target Element.
     pub fn remove(&self) {
-        self.event_target
-            .remove_event_listener_with_callback(&self.type_, self.closure.as_ref().unchecked_ref())
-            .expect("could not remove event listener");
+        match self.target {
+            ListenerTarget::Direct {
+                ref event_target,
+                ref closure,
+            } => {
+                event_target
+                    .remove_event_listener_with_callback_and_bool(
+                        &self.type_,
+                        closure.as_ref().unchecked_ref(),
+                        self.modifiers.capture,
+                    )
+                    .expect("could not remove event listener");
+            }
+            ListenerTarget::Delegated { ref delegator, id } => {
+                delegator.unregister(id, &self.handler, &self.type_);
+            }
+        }
+    }
+}
+
+impl Drop for EventListenerHandle {
+    fn drop(&mut self) {
+        self.remove();
     }
 }
 
@@ -75,6 +221,11 @@ pub trait Component: Sized + 'static {
     /// This method is called when the component is first mounted. It does not wait for children to
     /// render, only the top-level DOM element.
     fn on_mount(&mut self, _node: &Node) {}
+    /// This method is called after the view of the component and views of its children
+    /// are rendered to the DOM. `first_render` is `true` the first time it's called.
+    fn rendered(&mut self, _first_render: bool) {}
+    /// This method is called before the component is destroyed.
+    fn destroy(&mut self) {}
 }
 
 /// Should be rendered relative to context and component environment.
@@ -91,6 +242,10 @@ pub(crate) enum ComponentUpdate<COMP: Component> {
     Message(COMP::Message),
     /// Wraps properties for a component.
     Properties(COMP::Properties),
+    /// Renders the component if it still has a postponed render
+    Render,
+    /// Notifies the component that its view was rendered
+    Rendered(bool),
     /// Removes the component
     Destroy,
 }
@@ -149,27 +304,107 @@ where
         future_to_promise(js_future);
     }
 
+    /// This method processes a `std` future which resolves to a message and sends it back
+    /// to the component's loop. The future is dropped when the component is destroyed.
+    pub fn send_async<F>(&self, future: F)
+    where
+        F: std::future::Future<Output = COMP::Message> + 'static,
+    {
+        let mut scope = self.scope.clone();
+        self.scope
+            .spawn(future, move |message| scope.send_message(message));
+    }
+
+    /// This method processes a `std` future which resolves to a `Result` and sends it back
+    /// to the component's loop as a message, errors are turned into messages with `on_error`.
+    /// The future is dropped when the component is destroyed.
+    pub fn send_async_result<F, E, M>(&self, future: F, on_error: M)
+    where
+        F: std::future::Future<Output = Result<COMP::Message, E>> + 'static,
+        M: FnOnce(E) -> COMP::Message + 'static,
+    {
+        let mut scope = self.scope.clone();
+        self.scope.spawn(future, move |result| {
+            let message = result.unwrap_or_else(on_error);
+            scope.send_message(message);
+        });
+    }
+
     /// This method creates an event listener on the window for the specified event that
     /// will fire the closure and send the message to the message loop when fired.
-    pub fn connect_event<F, IN>(&self, event: &str, function: F)
+    /// The listener is removed when the returned handle is dropped.
+    pub fn connect_event<F, IN>(&self, event: &str, function: F) -> EventListenerHandle
     where
         F: Fn(IN) -> COMP::Message + 'static,
-        IN: wasm_bindgen::convert::FromWasmAbi + 'static,
+        IN: JsCast + 'static,
+    {
+        let window = window().expect("need a window context");
+        self.connect_event_to(&window, event, function)
+    }
+
+    /// This method creates an event listener on the target (ie. `document` or an element)
+    /// for the specified event that will fire the closure and send the message to the
+    /// message loop when fired. The listener is removed when the returned handle is dropped.
+    pub fn connect_event_to<F, IN>(
+        &self,
+        target: &EventTarget,
+        event: &str,
+        function: F,
+    ) -> EventListenerHandle
+    where
+        F: Fn(IN) -> COMP::Message + 'static,
+        IN: JsCast + 'static,
     {
         let scope = self.scope.clone();
-        let closure = Closure::wrap(Box::new(move |input| {
-            let output = function(input);
-            scope.clone().send_message(output);
-        }) as Box<dyn FnMut(IN)>);
+        let handler = Rc::new(move |event: web_sys::Event| match event.dyn_into::<IN>() {
+            Ok(input) => {
+                let output = function(input);
+                scope.clone().send_message(output);
+            }
+            Err(event) => {
+                error!(
+                    "could not cast event {} into the expected type",
+                    event.type_()
+                );
+            }
+        });
+        EventListenerHandle::new(target, handler, event)
+    }
 
-        // todo: handle error
-        window()
-            .expect("need a window context")
-            .add_event_listener_with_callback(event, closure.as_ref().unchecked_ref())
-            .expect("could not attach event listener");
+    /// This method provides the value to all descendants of the component. If the
+    /// component already provides a value of the type, the value is replaced and
+    /// subscribed descendants are notified if it has changed.
+    pub fn provide_context<T>(&self, value: T)
+    where
+        T: Clone + PartialEq + 'static,
+    {
+        self.scope.context.provide(value);
+    }
+
+    /// This method returns the value of the type provided by the nearest ancestor,
+    /// or `None` if no ancestor provides it.
+    pub fn context<T: Clone + 'static>(&self) -> Option<T> {
+        self.scope.context.inherited()
+    }
 
-        // todo: fix this memory leak
-        closure.forget();
+    /// This method returns the value of the type provided by the nearest ancestor
+    /// and sends a message made by the closure to the component's loop every time
+    /// the ancestor provides a new value. If no ancestor provides it yet, it returns
+    /// `None` and the message is sent once one does. Calling it again replaces the
+    /// subscription to values of the type, which is dropped when the component is destroyed.
+    pub fn subscribe_context<T, F>(&self, function: F) -> Option<T>
+    where
+        T: Clone + 'static,
+        F: Fn(T) -> COMP::Message + 'static,
+    {
+        let scope = self.scope.clone();
+        let subscriber: Subscriber<T> = Rc::new(move |value: &T| {
+            let output = function(value.clone());
+            scope.clone().send_message(output);
+        });
+        let value = Context::subscribe(&self.scope.context, &subscriber);
+        self.scope.keep_subscriber(subscriber);
+        value
     }
 }
 
@@ -177,14 +412,92 @@ where
 /// Mostly services uses it.
 pub struct Scope<COMP: Component> {
     shared_component: Shared<Option<ComponentRunnable<COMP>>>,
+    delegator: Option<Delegator>,
+    /// Renders caused by messages wait for the next animation frame.
+    batching: bool,
+    tasks: Shared<Vec<Weak<Task>>>,
+    context: Rc<Context>,
+    /// Subscribers to contexts of ancestors, one per type of a value.
+    subscriptions: Shared<AnyMap>,
 }
 
 impl<COMP: Component> Clone for Scope<COMP> {
     fn clone(&self) -> Self {
         Scope {
             shared_component: self.shared_component.clone(),
+            delegator: self.delegator.clone(),
+            batching: self.batching,
+            tasks: self.tasks.clone(),
+            context: self.context.clone(),
+            subscriptions: self.subscriptions.clone(),
+        }
+    }
+}
+
+impl<COMP: Component> Scope<COMP> {
+    /// Returns the delegator of the app if it delegates events.
+    pub(crate) fn delegator(&self) -> Option<Delegator> {
+        self.delegator.clone()
+    }
+
+    /// Spawns a future which is cancelled when the component is destroyed.
+    fn spawn<F, C>(&self, future: F, callback: C)
+    where
+        F: std::future::Future + 'static,
+        C: FnOnce(F::Output) + 'static,
+    {
+        let task = Task::spawn(future, callback);
+        let mut tasks = self.tasks.borrow_mut();
+        tasks.retain(|task| task.strong_count() > 0);
+        tasks.push(Rc::downgrade(&task));
+    }
+
+    /// Returns the first DOM node of the view the component has rendered.
+    pub(crate) fn first_node(&self) -> Option<Node> {
+        let component = self.shared_component.try_borrow().ok()?;
+        component.as_ref()?.last_frame.as_ref()?.first_node()
+    }
+
+    /// Moves the DOM nodes of the view the component has rendered before `next`.
+    pub(crate) fn move_before(&self, parent: &Node, next: Option<&Node>) {
+        if let Ok(component) = self.shared_component.try_borrow() {
+            if let Some(frame) = component.as_ref().and_then(|this| this.last_frame.as_ref()) {
+                frame.move_before(parent, next);
+            }
+        }
+    }
+
+    /// Cancels all pending futures of the component.
+    fn cancel_tasks(&self) {
+        let tasks: Vec<_> = self.tasks.borrow_mut().drain(..).collect();
+        for task in tasks.iter().filter_map(Weak::upgrade) {
+            task.cancel();
         }
     }
+
+    /// Returns the context which keeps values provided by the component.
+    pub(crate) fn context(&self) -> Rc<Context> {
+        self.context.clone()
+    }
+
+    /// Replaces the context, so components mounted with the scope inherit values of it.
+    pub(crate) fn with_context(mut self, context: Rc<Context>) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// Keeps the subscriber alive until the component is destroyed, it replaces
+    /// the previous subscriber to values of the type.
+    fn keep_subscriber<T: 'static>(&self, subscriber: Subscriber<T>) {
+        let previous = self.subscriptions.borrow_mut().insert(subscriber);
+        drop(previous);
+    }
+
+    /// Drops subscriptions of the component to contexts of its ancestors.
+    fn cancel_subscriptions(&self) {
+        let subscriptions = mem::replace(&mut *self.subscriptions.borrow_mut(), AnyMap::new());
+        drop(subscriptions);
+    }
 }
 
 impl<COMP> Scope<COMP>
@@ -201,6 +514,15 @@ where
         scheduler().put_and_try_run(runnable);
     }
 
+    /// Schedules the `rendered` hook which runs when children are rendered too.
+    fn schedule_rendered(&self, first_render: bool) {
+        let envelope = ComponentEnvelope {
+            shared_component: self.shared_component.clone(),
+            message: Some(ComponentUpdate::Rendered(first_render)),
+        };
+        scheduler().put_after_render(Box::new(envelope));
+    }
+
     /// Send message to a component.
     pub fn send_message(&mut self, message: COMP::Message) {
         let update = ComponentUpdate::Message(message);
@@ -211,13 +533,91 @@ where
 /// Holder for the element.
 pub type NodeCell = Rc<RefCell<Option<Node>>>;
 
+/// A reference to a DOM node rendered by `html!`. Pass it to a tag with `ref=self.node_ref,`
+/// and it's filled in when the tag is rendered and cleared when the tag is removed.
+#[derive(Default, Clone)]
+pub struct NodeRef(NodeCell);
+
+impl NodeRef {
+    /// Returns the rendered node if there is one.
+    pub fn get(&self) -> Option<Node> {
+        self.0.borrow().clone()
+    }
+
+    /// Returns the rendered node converted to `T` if there is one and it has that type.
+    pub fn cast<T: JsCast>(&self) -> Option<T> {
+        self.get().and_then(|node| node.dyn_into().ok())
+    }
+
+    pub(crate) fn set(&self, node: Option<Node>) {
+        *self.0.borrow_mut() = node;
+    }
+
+    /// Clears the reference if it still refers to the node.
+    pub(crate) fn clear_if(&self, node: &Node) {
+        let refers = self
+            .0
+            .borrow()
+            .as_ref()
+            .is_some_and(|n| n.is_same_node(Some(node)));
+        if refers {
+            self.set(None);
+        }
+    }
+}
+
+impl PartialEq for NodeRef {
+    fn eq(&self, other: &NodeRef) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl fmt::Debug for NodeRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "NodeRef {{ set: {} }}", self.0.borrow().is_some())
+    }
+}
+
 impl<COMP> Scope<COMP>
 where
     COMP: Component + Renderable<COMP>,
 {
     pub(crate) fn new() -> Self {
         let shared_component = Rc::new(RefCell::new(None));
-        Scope { shared_component }
+        Scope {
+            shared_component,
+            delegator: None,
+            batching: false,
+            tasks: Rc::new(RefCell::new(Vec::new())),
+            context: Context::new(None),
+            subscriptions: Rc::new(RefCell::new(AnyMap::new())),
+        }
+    }
+
+    /// Makes the scope a child of the `parent` scope, so it shares the delegator
+    /// and the batching mode of the parent and inherits values of its context.
+    pub(crate) fn with_parent<PARENT: Component>(mut self, parent: &Scope<PARENT>) -> Self {
+        self.delegator = parent.delegator();
+        self.batching = parent.batching;
+        self.with_parent_context(Some(parent.context()))
+    }
+
+    /// Sets the context which the scope inherits values from.
+    pub(crate) fn with_parent_context(mut self, parent: Option<Rc<Context>>) -> Self {
+        self.context = Context::new(parent);
+        self
+    }
+
+    /// Sets the delegator which handles events of elements rendered by the component.
+    pub(crate) fn with_delegator(mut self, delegator: Option<Delegator>) -> Self {
+        self.delegator = delegator;
+        self
+    }
+
+    /// Makes renders caused by messages wait for the next animation frame.
+    pub(crate) fn with_batching(mut self, batching: bool) -> Self {
+        self.batching = batching;
+        self
     }
 
     // TODO Consider to use &Node instead of Element as parent
@@ -228,36 +628,108 @@ where
         ancestor: Option<VNode<COMP>>,
         occupied: Option<NodeCell>,
         init_props: Option<COMP::Properties>,
+    ) -> Scope<COMP> {
+        let mut scope = self.init(element, ancestor, occupied, init_props, false);
+        let link = ComponentLink::connect(&scope);
+        scope.send(ComponentUpdate::Create(link));
+        scope
+    }
+
+    /// Mounts elements over the existing `node` adopting it instead of rendering a new one.
+    pub(crate) fn hydrate_in_place(
+        self,
+        element: Element,
+        node: Option<Node>,
+        occupied: Option<NodeCell>,
+        init_props: Option<COMP::Properties>,
+    ) -> Scope<COMP> {
+        let ancestor = node.map(VNode::VRef);
+        let scope = self.init(element, ancestor, occupied, init_props, true);
+        let link = ComponentLink::connect(&scope);
+        let envelope = ComponentEnvelope {
+            shared_component: scope.shared_component.clone(),
+            message: Some(ComponentUpdate::Create(link)),
+        };
+        // Existing nodes have to be claimed in order, so the component can't
+        // wait until a parent component finishes its rendering.
+        scheduler().run_now(Box::new(envelope));
+        scope
+    }
+
+    fn init(
+        self,
+        element: Element,
+        ancestor: Option<VNode<COMP>>,
+        occupied: Option<NodeCell>,
+        init_props: Option<COMP::Properties>,
+        hydrate: bool,
     ) -> Scope<COMP> {
         let runnable = ComponentRunnable {
             env: self.clone(),
+            order: MOUNT_ORDER.fetch_add(1, Ordering::Relaxed),
             component: None,
             last_frame: None,
             element,
             ancestor,
             occupied,
             init_props,
+            hydrate,
+            dirty: false,
             destroyed: false,
         };
-        let mut scope = self.clone();
-        *scope.shared_component.borrow_mut() = Some(runnable);
-        let link = ComponentLink::connect(&scope);
-        scope.send(ComponentUpdate::Create(link));
-        scope
+        *self.shared_component.borrow_mut() = Some(runnable);
+        self
+    }
+
+    /// Creates the component and renders its view to HTML markup without mounting it.
+    /// Messages sent to the component from `create` are ignored.
+    pub(crate) fn render_to_string(self, init_props: Option<COMP::Properties>) -> String {
+        let link = ComponentLink::connect(&self);
+        let component = COMP::create(init_props.unwrap_or_default(), link);
+        let mut view = component.view();
+        view.set_render_context(&self.context);
+        view.render_to_string()
     }
 }
 
+/// Counter of mounted components. Parents are always mounted before their children.
+static MOUNT_ORDER: AtomicUsize = AtomicUsize::new(0);
+
 struct ComponentRunnable<COMP: Component> {
     env: Scope<COMP>,
+    order: usize,
     component: Option<COMP>,
     last_frame: Option<VNode<COMP>>,
     element: Element,
     ancestor: Option<VNode<COMP>>,
     occupied: Option<NodeCell>,
     init_props: Option<COMP::Properties>,
+    hydrate: bool,
+    /// The component has a render postponed until the next animation frame.
+    dirty: bool,
     destroyed: bool,
 }
 
+impl<COMP> ComponentRunnable<COMP>
+where
+    COMP: Component + Renderable<COMP>,
+{
+    /// Renders the component and diffs the new view against the previous one.
+    fn render(&mut self) {
+        let env = self.env.clone();
+        let mut next_frame = self.component.as_ref().unwrap().view();
+        // Scheduled before children are updated, so it runs after theirs
+        self.env.schedule_rendered(false);
+        // Re-rendering the tree
+        let node = next_frame.apply(&self.element, None, self.last_frame.take(), &env);
+        if let Some(ref mut cell) = self.occupied {
+            *cell.borrow_mut() = node;
+        }
+        self.last_frame = Some(next_frame);
+        self.dirty = false;
+    }
+}
+
 /// Wraps a component reference and a message to hide it under `Runnable` trait.
 /// It's necessary to schedule a processing of a message.
 struct ComponentEnvelope<COMP>
@@ -274,7 +746,14 @@ where
 {
     fn run(&mut self) {
         let mut component = self.shared_component.borrow_mut();
-        let this = component.as_mut().expect("shared component not set");
+        let this = match component.as_mut() {
+            Some(this) => this,
+            None => {
+                // The component was only rendered to a string and has never been mounted
+                debug!("ignored an update for a component which isn't mounted");
+                return;
+            }
+        };
         if this.destroyed {
             return;
         }
@@ -295,13 +774,27 @@ where
                 // No messages at start
                 let current_frame = this.component.as_ref().unwrap().view();
                 this.last_frame = Some(current_frame);
+                // Scheduled before children are created, because hydrated children
+                // run right away and have to be notified before the parent
+                env.schedule_rendered(true);
                 // First-time rendering the tree
-                let node = this.last_frame.as_mut().unwrap().apply(
-                    &this.element,
-                    None,
-                    this.ancestor.take(),
-                    &env,
-                );
+                let ancestor = this.ancestor.take();
+                let node = if this.hydrate {
+                    // The ancestor is the existing node to adopt
+                    let node = match ancestor {
+                        Some(VNode::VRef(node)) => Some(node),
+                        _ => None,
+                    };
+                    this.last_frame
+                        .as_mut()
+                        .unwrap()
+                        .hydrate(&this.element, node, &env)
+                } else {
+                    this.last_frame
+                        .as_mut()
+                        .unwrap()
+                        .apply(&this.element, None, ancestor, &env)
+                };
                 if let Some(ref node) = node {
                     this.component.as_mut().unwrap().on_mount(node);
                 }
@@ -310,11 +803,24 @@ where
                 }
             }
             ComponentUpdate::Message(msg) => {
-                should_update |= this
+                let should_render = this
                     .component
                     .as_mut()
                     .expect("component was not created to process messages")
                     .update(msg);
+                if should_render && env.batching {
+                    // The render waits for the next animation frame
+                    if !this.dirty {
+                        this.dirty = true;
+                        let envelope = ComponentEnvelope {
+                            shared_component: self.shared_component.clone(),
+                            message: Some(ComponentUpdate::Render),
+                        };
+                        scheduler().put_render(this.order, Box::new(envelope));
+                    }
+                } else {
+                    should_update |= should_render;
+                }
             }
             ComponentUpdate::Properties(props) => {
                 should_update |= this
@@ -323,19 +829,32 @@ where
                     .expect("component was not created to process properties")
                     .change(props);
             }
+            ComponentUpdate::Render => {
+                // The component could be re-rendered by its parent in the meantime
+                should_update |= this.dirty;
+            }
+            ComponentUpdate::Rendered(first_render) => {
+                this.component
+                    .as_mut()
+                    .expect("component was not created to be notified about rendering")
+                    .rendered(first_render);
+            }
             ComponentUpdate::Destroy => {
-                this.component.take();
+                // Late responses must never hit a destroyed component
+                env.cancel_tasks();
+                env.cancel_subscriptions();
+                if let Some(mut component) = this.component.take() {
+                    component.destroy();
+                }
+                // Nested components are removed together with the node of this one
+                if let Some(mut frame) = this.last_frame.take() {
+                    frame.destroy_components();
+                }
                 this.destroyed = true;
             }
         }
         if should_update {
-            let mut next_frame = this.component.as_ref().unwrap().view();
-            // Re-rendering the tree
-            let node = next_frame.apply(&this.element, None, this.last_frame.take(), &env);
-            if let Some(ref mut cell) = this.occupied {
-                *cell.borrow_mut() = node;
-            }
-            this.last_frame = Some(next_frame);
+            this.render();
         }
     }
 }
@@ -379,22 +898,23 @@ macro_rules! impl_action {
                     stringify!($action)
                 }
 
-                fn attach(&mut self, element: &Element, mut activator: Scope<COMP>)
-                    -> EventListenerHandle {
+                fn event_type(&self) -> &'static str {
+                    stringify!($event)
+                }
+
+                fn handler(&mut self, element: &Element, activator: Scope<COMP>) -> EventHandler {
                     let handler = self.0.take().expect("tried to attach listener twice");
                     let this = element.clone();
-                    let listener = Closure::wrap(Box::new(move |event: web_sys::Event| {
+                    Rc::new(move |event: web_sys::Event| {
                         debug!("Event handler: {}", stringify!($type));
                         if let Ok(event) = event.dyn_into::<$type>() {
-                            event.stop_propagation();
                             let handy_event: $ret = $convert(&this, event);
                             let msg = handler(handy_event);
-                            activator.send_message(msg);
+                            activator.clone().send_message(msg);
                         } else {
                             error!("could not cast event into {}", stringify!($type));
                         }
-                    }) as Box<dyn FnMut(web_sys::Event)>);
-                    EventListenerHandle::new(element, listener, stringify!($event))
+                    })
                 }
             }
         }
@@ -404,7 +924,7 @@ macro_rules! impl_action {
 // Inspired by: http://package.elm-lang.org/packages/elm-lang/html/2.0.0/Html-Events
 impl_action! {
     onclick(click: MouseEvent) -> MouseEvent => |_, event| { event }
-    ondoubleclick(doubleclick: MouseEvent) -> MouseEvent => |_, event| { event }
+    ondoubleclick(dblclick: MouseEvent) -> MouseEvent => |_, event| { event }
     onkeypress(keypress: KeyboardEvent) -> KeyboardEvent => |_, event| { event }
     onkeydown(keydown: KeyboardEvent) -> KeyboardEvent => |_, event| { event }
     onkeyup(keyup: KeyboardEvent) -> KeyboardEvent => |_, event| { event }
@@ -439,6 +959,56 @@ impl_action! {
     ondragexit(dragexit: DragEvent) -> DragEvent => |_, event| { event }
     ondrop(drop: DragEvent) -> DragEvent => |_, event| { event }
     oncontextmenu(contextmenu: MouseEvent) -> MouseEvent => |_, event| { event }
+    onauxclick(auxclick: MouseEvent) -> MouseEvent => |_, event| { event }
+    onwheel(wheel: WheelEvent) -> WheelEvent => |_, event| { event }
+    ontouchstart(touchstart: TouchEvent) -> TouchEvent => |_, event| { event }
+    ontouchmove(touchmove: TouchEvent) -> TouchEvent => |_, event| { event }
+    ontouchend(touchend: TouchEvent) -> TouchEvent => |_, event| { event }
+    ontouchcancel(touchcancel: TouchEvent) -> TouchEvent => |_, event| { event }
+    onfocusin(focusin: FocusEvent) -> FocusEvent => |_, event| { event }
+    onfocusout(focusout: FocusEvent) -> FocusEvent => |_, event| { event }
+    onbeforeinput(beforeinput: InputEvent) -> InputEvent => |_, event| { event }
+    onreset(reset: Event) -> Event => |_, event| { event }
+    oninvalid(invalid: Event) -> Event => |_, event| { event }
+    onselect(select: Event) -> Event => |_, event| { event }
+    ontoggle(toggle: Event) -> Event => |_, event| { event }
+    oncopy(copy: ClipboardEvent) -> ClipboardEvent => |_, event| { event }
+    oncut(cut: ClipboardEvent) -> ClipboardEvent => |_, event| { event }
+    onpaste(paste: ClipboardEvent) -> ClipboardEvent => |_, event| { event }
+    oncompositionstart(compositionstart: CompositionEvent) -> CompositionEvent => |_, event| { event }
+    oncompositionupdate(compositionupdate: CompositionEvent) -> CompositionEvent => |_, event| { event }
+    oncompositionend(compositionend: CompositionEvent) -> CompositionEvent => |_, event| { event }
+    onanimationstart(animationstart: AnimationEvent) -> AnimationEvent => |_, event| { event }
+    onanimationiteration(animationiteration: AnimationEvent) -> AnimationEvent => |_, event| { event }
+    onanimationend(animationend: AnimationEvent) -> AnimationEvent => |_, event| { event }
+    onanimationcancel(animationcancel: AnimationEvent) -> AnimationEvent => |_, event| { event }
+    ontransitionrun(transitionrun: TransitionEvent) -> TransitionEvent => |_, event| { event }
+    ontransitionstart(transitionstart: TransitionEvent) -> TransitionEvent => |_, event| { event }
+    ontransitionend(transitionend: TransitionEvent) -> TransitionEvent => |_, event| { event }
+    ontransitioncancel(transitioncancel: TransitionEvent) -> TransitionEvent => |_, event| { event }
+    onload(load: Event) -> Event => |_, event| { event }
+    onerror(error: Event) -> Event => |_, event| { event }
+    onabort(abort: Event) -> Event => |_, event| { event }
+    oncanplay(canplay: Event) -> Event => |_, event| { event }
+    oncanplaythrough(canplaythrough: Event) -> Event => |_, event| { event }
+    ondurationchange(durationchange: Event) -> Event => |_, event| { event }
+    onemptied(emptied: Event) -> Event => |_, event| { event }
+    onended(ended: Event) -> Event => |_, event| { event }
+    onloadeddata(loadeddata: Event) -> Event => |_, event| { event }
+    onloadedmetadata(loadedmetadata: Event) -> Event => |_, event| { event }
+    onloadstart(loadstart: Event) -> Event => |_, event| { event }
+    onpause(pause: Event) -> Event => |_, event| { event }
+    onplay(play: Event) -> Event => |_, event| { event }
+    onplaying(playing: Event) -> Event => |_, event| { event }
+    onprogress(progress: Event) -> Event => |_, event| { event }
+    onratechange(ratechange: Event) -> Event => |_, event| { event }
+    onseeked(seeked: Event) -> Event => |_, event| { event }
+    onseeking(seeking: Event) -> Event => |_, event| { event }
+    onstalled(stalled: Event) -> Event => |_, event| { event }
+    onsuspend(suspend: Event) -> Event => |_, event| { event }
+    ontimeupdate(timeupdate: Event) -> Event => |_, event| { event }
+    onvolumechange(volumechange: Event) -> Event => |_, event| { event }
+    onwaiting(waiting: Event) -> Event => |_, event| { event }
     oninput(input: InputEvent) -> InputData => |this: &Element, _| {
         use web_sys::{HtmlInputElement, HtmlTextAreaElement};
         let value = match this.clone().dyn_into() {
@@ -512,15 +1082,56 @@ where
         self.action
     }
 
-    fn attach(&mut self, element: &Element, mut activator: Scope<COMP>) -> EventListenerHandle {
+    fn event_type(&self) -> &'static str {
+        self.action
+    }
+
+    fn handler(&mut self, _: &Element, activator: Scope<COMP>) -> EventHandler {
         let handler = self.handler.take().expect("tried to attach listener twice");
-        let listener = Closure::wrap(Box::new(move |event: web_sys::Event| {
+        Rc::new(move |event: web_sys::Event| {
             debug!("Event handler: generic");
-            event.stop_propagation();
             let msg = handler(event);
-            activator.send_message(msg);
-        }) as Box<dyn FnMut(web_sys::Event)>);
-        EventListenerHandle::new(element, listener, self.action)
+            activator.clone().send_message(msg);
+        })
+    }
+}
+
+/// A listener with modifiers set in a template (ie. `onclick.stop=|_| Msg::Close`).
+pub struct Modified<L> {
+    listener: L,
+    modifiers: Modifiers,
+}
+
+impl<L> Modified<L> {
+    /// Sets modifiers of the listener.
+    pub fn new(listener: L, modifiers: Modifiers) -> Modified<L> {
+        Modified {
+            listener,
+            modifiers,
+        }
+    }
+}
+
+impl<L, COMP> Listener<COMP> for Modified<L>
+where
+    L: Listener<COMP>,
+    COMP: Component,
+{
+    fn kind(&self) -> &'static str {
+        self.listener.kind()
+    }
+
+    fn event_type(&self) -> &'static str {
+        self.listener.event_type()
+    }
+
+    fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    fn handler(&mut self, element: &Element, activator: Scope<COMP>) -> EventHandler {
+        self.modifiers
+            .wrap(self.listener.handler(element, activator))
     }
 }
 
@@ -66,19 +66,25 @@
 
 #[macro_use]
 extern crate log;
+extern crate anymap;
+extern crate bincode;
 extern crate futures;
 extern crate js_sys;
+extern crate serde;
+extern crate slab;
 extern crate wasm_bindgen;
 extern crate wasm_bindgen_futures;
 extern crate web_sys;
 
 #[macro_use]
 pub mod macros;
-// todo: figure out what to do with this
-// pub mod agent;
+pub mod agent;
 pub mod app;
 pub mod callback;
 pub mod components;
+mod context;
+mod delegation;
+mod executor;
 pub mod html;
 pub mod prelude;
 pub mod scheduler;
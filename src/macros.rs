@@ -1,8 +1,9 @@
 //! This module contains macros which implements `html!` macro
 //! and JSX-like templates.
 
-use html::Component;
-use virtual_dom::{Listener, VNode};
+use html::{Component, NodeRef};
+use virtual_dom::{Listener, Style, VNode};
+use wasm_bindgen::JsValue;
 
 /// some docs
 #[macro_export]
@@ -27,6 +28,11 @@ macro_rules! html_impl {
         $pair.0 = $props;
         html_impl! { @vcomp $stack $pair ($($tail)*) }
     };
+    // PATTERN: key=expression,
+    (@vcomp $stack:ident $pair:ident (key = $key:expr, $($tail:tt)*)) => {
+        $pair.1.set_key(&$key);
+        html_impl! { @vcomp $stack $pair ($($tail)*) }
+    };
     // Set a specific field as a property.
     // It uses `Transformer` trait to convert a type used in template to a type of the field.
     (@vcomp $stack:ident $pair:ident ($attr:ident = $val:expr, $($tail:tt)*)) => {
@@ -44,6 +50,16 @@ macro_rules! html_impl {
         $crate::macros::child_to_parent(&mut $stack, None);
         html_impl! { $stack ($($tail)*) }
     };
+    // End of opening tag of a component with children.
+    // The children are collected in a list until the closing tag.
+    (@vcomp $stack:ident $pair:ident (> $($tail:tt)*)) => {
+        let (mut props, mut comp) = $pair;
+        props.children = comp.children();
+        comp.set_props(props);
+        $stack.push(comp.into());
+        $stack.push($crate::virtual_dom::VList::new().into());
+        html_impl! { $stack ($($tail)*) }
+    };
     // Start of opening tag
     ($stack:ident (< $starttag:ident $($tail:tt)*)) => {
         let vtag = $crate::virtual_dom::VTag::new(stringify!($starttag).replace("_", "-"));
@@ -59,6 +75,26 @@ macro_rules! html_impl {
         $crate::macros::set_classes(&mut $stack, $class);
         html_impl! { @vtag $stack ($($tail)*) }
     };
+    // PATTERN: key=expression,
+    (@vtag $stack:ident (key = $key:expr, $($tail:tt)*)) => {
+        $crate::macros::set_key(&mut $stack, $key);
+        html_impl! { @vtag $stack ($($tail)*) }
+    };
+    // PATTERN: ref=expression,
+    (@vtag $stack:ident (ref = $node_ref:expr, $($tail:tt)*)) => {
+        $crate::macros::set_node_ref(&mut $stack, &$node_ref);
+        html_impl! { @vtag $stack ($($tail)*) }
+    };
+    // PATTERN: style=expression, - a string, a map, a list of pairs or a `Style`
+    (@vtag $stack:ident (style = $style:expr, $($tail:tt)*)) => {
+        $crate::macros::set_style(&mut $stack, $style);
+        html_impl! { @vtag $stack ($($tail)*) }
+    };
+    // PATTERN: .property=expression, - a JS property instead of an attribute
+    (@vtag $stack:ident (. $property:ident = $value:expr, $($tail:tt)*)) => {
+        $crate::macros::set_property(&mut $stack, stringify!($property), $value);
+        html_impl! { @vtag $stack ($($tail)*) }
+    };
     // PATTERN: value="",
     (@vtag $stack:ident (value = $value:expr, $($tail:tt)*)) => {
         $crate::macros::set_value_or_attribute(&mut $stack, $value);
@@ -201,6 +237,156 @@ macro_rules! html_impl {
     (@vtag $stack:ident (oncontextmenu = | $var:pat | $handler:expr, $($tail:tt)*)) => {
         html_impl! { @vtag $stack ((oncontextmenu) = move | $var: $crate::prelude::MouseEvent | $handler, $($tail)*) }
     };
+    (@vtag $stack:ident (onauxclick = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((onauxclick) = move | $var: $crate::prelude::MouseEvent | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (onwheel = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((onwheel) = move | $var: $crate::prelude::WheelEvent | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (ontouchstart = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((ontouchstart) = move | $var: $crate::prelude::TouchEvent | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (ontouchmove = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((ontouchmove) = move | $var: $crate::prelude::TouchEvent | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (ontouchend = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((ontouchend) = move | $var: $crate::prelude::TouchEvent | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (ontouchcancel = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((ontouchcancel) = move | $var: $crate::prelude::TouchEvent | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (onfocusin = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((onfocusin) = move | $var: $crate::prelude::FocusEvent | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (onfocusout = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((onfocusout) = move | $var: $crate::prelude::FocusEvent | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (onbeforeinput = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((onbeforeinput) = move | $var: $crate::prelude::InputEvent | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (onreset = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((onreset) = move | $var: $crate::prelude::Event | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (oninvalid = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((oninvalid) = move | $var: $crate::prelude::Event | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (onselect = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((onselect) = move | $var: $crate::prelude::Event | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (ontoggle = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((ontoggle) = move | $var: $crate::prelude::Event | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (oncopy = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((oncopy) = move | $var: $crate::prelude::ClipboardEvent | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (oncut = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((oncut) = move | $var: $crate::prelude::ClipboardEvent | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (onpaste = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((onpaste) = move | $var: $crate::prelude::ClipboardEvent | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (oncompositionstart = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((oncompositionstart) = move | $var: $crate::prelude::CompositionEvent | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (oncompositionupdate = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((oncompositionupdate) = move | $var: $crate::prelude::CompositionEvent | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (oncompositionend = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((oncompositionend) = move | $var: $crate::prelude::CompositionEvent | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (onanimationstart = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((onanimationstart) = move | $var: $crate::prelude::AnimationEvent | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (onanimationiteration = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((onanimationiteration) = move | $var: $crate::prelude::AnimationEvent | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (onanimationend = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((onanimationend) = move | $var: $crate::prelude::AnimationEvent | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (onanimationcancel = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((onanimationcancel) = move | $var: $crate::prelude::AnimationEvent | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (ontransitionrun = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((ontransitionrun) = move | $var: $crate::prelude::TransitionEvent | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (ontransitionstart = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((ontransitionstart) = move | $var: $crate::prelude::TransitionEvent | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (ontransitionend = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((ontransitionend) = move | $var: $crate::prelude::TransitionEvent | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (ontransitioncancel = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((ontransitioncancel) = move | $var: $crate::prelude::TransitionEvent | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (onload = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((onload) = move | $var: $crate::prelude::Event | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (onerror = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((onerror) = move | $var: $crate::prelude::Event | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (onabort = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((onabort) = move | $var: $crate::prelude::Event | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (oncanplay = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((oncanplay) = move | $var: $crate::prelude::Event | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (oncanplaythrough = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((oncanplaythrough) = move | $var: $crate::prelude::Event | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (ondurationchange = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((ondurationchange) = move | $var: $crate::prelude::Event | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (onemptied = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((onemptied) = move | $var: $crate::prelude::Event | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (onended = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((onended) = move | $var: $crate::prelude::Event | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (onloadeddata = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((onloadeddata) = move | $var: $crate::prelude::Event | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (onloadedmetadata = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((onloadedmetadata) = move | $var: $crate::prelude::Event | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (onloadstart = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((onloadstart) = move | $var: $crate::prelude::Event | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (onpause = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((onpause) = move | $var: $crate::prelude::Event | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (onplay = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((onplay) = move | $var: $crate::prelude::Event | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (onplaying = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((onplaying) = move | $var: $crate::prelude::Event | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (onprogress = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((onprogress) = move | $var: $crate::prelude::Event | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (onratechange = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((onratechange) = move | $var: $crate::prelude::Event | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (onseeked = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((onseeked) = move | $var: $crate::prelude::Event | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (onseeking = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((onseeking) = move | $var: $crate::prelude::Event | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (onstalled = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((onstalled) = move | $var: $crate::prelude::Event | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (onsuspend = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((onsuspend) = move | $var: $crate::prelude::Event | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (ontimeupdate = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((ontimeupdate) = move | $var: $crate::prelude::Event | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (onvolumechange = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((onvolumechange) = move | $var: $crate::prelude::Event | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (onwaiting = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack ((onwaiting) = move | $var: $crate::prelude::Event | $handler, $($tail)*) }
+    };
 
     // PATTERN: (action)=expression,
     (@vtag $stack:ident (($action:ident) = $handler:expr, $($tail:tt)*)) => {
@@ -219,6 +405,32 @@ macro_rules! html_impl {
         $crate::macros::attach_listener(&mut $stack, Box::new(listener));
         html_impl! { @vtag $stack ($($tail)*) }
     };
+    // PATTERN: action.modifier=expression,
+    // Modifiers are fields of `Modifiers` (ie. `onclick.stop.prevent=|_| Msg::Submit,`)
+    (@vtag $stack:ident ($action:ident . $($modifier:ident).+ = | $var:pat | $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack (($action) . $($modifier).+ = move | $var: $crate::html::$action::EventTy | $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident ($action:ident . $($modifier:ident).+ = $handler:expr, $($tail:tt)*)) => {
+        html_impl! { @vtag $stack (($action) . $($modifier).+ = $handler, $($tail)*) }
+    };
+    (@vtag $stack:ident (($action:ident) . $($modifier:ident).+ = $handler:expr, $($tail:tt)*)) => {
+        let handler = $handler;
+        let listener = $crate::html::$action::Wrapper::from(handler);
+        let mut modifiers = $crate::html::Modifiers::default();
+        $( modifiers.$modifier = true; )+
+        let listener = $crate::html::Modified::new(listener, modifiers);
+        $crate::macros::attach_listener(&mut $stack, Box::new(listener));
+        html_impl! { @vtag $stack ($($tail)*) }
+    };
+    (@vtag $stack:ident ([$action:ident] . $($modifier:ident).+ = $handler:expr, $($tail:tt)*)) => {
+        let handler = $handler;
+        let listener = $crate::html::GenericAction::new(stringify!($action), handler);
+        let mut modifiers = $crate::html::Modifiers::default();
+        $( modifiers.$modifier = true; )+
+        let listener = $crate::html::Modified::new(listener, modifiers);
+        $crate::macros::attach_listener(&mut $stack, Box::new(listener));
+        html_impl! { @vtag $stack ($($tail)*) }
+    };
     // Attributes:
     (@vtag $stack:ident (href = $href:expr, $($tail:tt)*)) => {
         let href: $crate::html::Href = $href.into();
@@ -238,6 +450,12 @@ macro_rules! html_impl {
         $crate::macros::child_to_parent(&mut $stack, None);
         html_impl! { $stack ($($tail)*) }
     };
+    // PATTERN: prefix:attribute=value, - namespaced attributes like `xlink:href`
+    (@vtag $stack:ident ($prefix:ident : $($attr:ident)-+ = $val:expr, $($tail:tt)*)) => {
+        let attr = format!("{}:{}", stringify!($prefix), vec![$(stringify!($attr)),+].join("-"));
+        $crate::macros::add_attribute(&mut $stack, &attr, $val);
+        html_impl! { @vtag $stack ($($tail)*) }
+    };
     (@vtag $stack:ident ($($attr:ident)-+ = $val:expr, $($tail:tt)*)) => {
         let attr = vec![$(stringify!($attr).to_string()),+].join("-");
         $crate::macros::add_attribute(&mut $stack, &attr, $val);
@@ -316,6 +534,24 @@ pub fn set_value_or_attribute<COMP: Component, T: ToString>(stack: &mut Stack<CO
     }
 }
 
+#[doc(hidden)]
+pub fn set_key<COMP: Component, T: ToString>(stack: &mut Stack<COMP>, key: T) {
+    if let Some(&mut VNode::VTag(ref mut vtag)) = stack.last_mut() {
+        vtag.set_key(&key);
+    } else {
+        panic!("no tag to set key: {}", key.to_string());
+    }
+}
+
+#[doc(hidden)]
+pub fn set_node_ref<COMP: Component>(stack: &mut Stack<COMP>, node_ref: &NodeRef) {
+    if let Some(&mut VNode::VTag(ref mut vtag)) = stack.last_mut() {
+        vtag.set_node_ref(node_ref);
+    } else {
+        panic!("no tag to set ref");
+    }
+}
+
 #[doc(hidden)]
 pub fn set_kind<COMP: Component, T: ToString>(stack: &mut Stack<COMP>, value: T) {
     if let Some(&mut VNode::VTag(ref mut vtag)) = stack.last_mut() {
@@ -335,6 +571,28 @@ pub fn set_checked<COMP: Component>(stack: &mut Stack<COMP>, value: bool) {
     }
 }
 
+#[doc(hidden)]
+pub fn set_style<COMP: Component, T: Into<Style>>(stack: &mut Stack<COMP>, style: T) {
+    if let Some(&mut VNode::VTag(ref mut vtag)) = stack.last_mut() {
+        vtag.set_style(style);
+    } else {
+        panic!("no tag to set style");
+    }
+}
+
+#[doc(hidden)]
+pub fn set_property<COMP: Component, T: Into<JsValue>>(
+    stack: &mut Stack<COMP>,
+    name: &str,
+    value: T,
+) {
+    if let Some(&mut VNode::VTag(ref mut vtag)) = stack.last_mut() {
+        vtag.set_property(name, value);
+    } else {
+        panic!("no tag to set property: {}", name);
+    }
+}
+
 #[doc(hidden)]
 pub fn add_attribute<COMP: Component, T: ToString>(stack: &mut Stack<COMP>, name: &str, value: T) {
     if let Some(&mut VNode::VTag(ref mut vtag)) = stack.last_mut() {
@@ -403,6 +661,17 @@ pub fn child_to_parent<COMP: Component>(stack: &mut Stack<COMP>, endtag: Option<
                 panic!("wrong closing tag: <{}> -> </{}>", starttag, endtag);
             }
         }
+        // The list of children of a component is closed together with the component
+        let node = match (node, stack.last_mut(), endtag) {
+            (VNode::VList(vlist), Some(&mut VNode::VComp(ref mut vcomp)), Some(endtag)) => {
+                if vcomp.name() != endtag {
+                    panic!("wrong closing tag: <{}> -> </{}>", vcomp.name(), endtag);
+                }
+                vcomp.set_children(vlist);
+                stack.pop().expect("no component in the stack")
+            }
+            (node, _, _) => node,
+        };
         // Push the popped element to the last in the stack
         if !stack.is_empty() {
             match stack.last_mut() {
@@ -7,25 +7,28 @@
 //! use yew::prelude::*;
 //! ```
 pub use html::{
-    ChangeData, Component, ComponentLink, Href, Html, InputData, Renderable, ShouldRender,
+    ChangeData, Component, ComponentLink, EventListenerHandle, Href, Html, InputData, NodeRef,
+    Renderable, ShouldRender,
 };
 
 pub use app::App;
 
 pub use callback::Callback;
 
+pub use virtual_dom::{Children, Style};
+
 pub use web_sys::{
-    DragEvent, Event, FocusEvent, InputEvent, KeyEvent, KeyboardEvent, MouseEvent,
-    MouseScrollEvent, Node as HtmlNode, PointerEvent,
+    AnimationEvent, ClipboardEvent, CompositionEvent, DragEvent, Event, FocusEvent, InputEvent,
+    KeyEvent, KeyboardEvent, MouseEvent, MouseScrollEvent, Node as HtmlNode, PointerEvent,
+    TouchEvent, TransitionEvent, WheelEvent,
 };
 
-// todo: figure out what to do with this
-// pub use agent::{Bridge, Bridged, Threaded};
+pub use agent::{Bridge, Bridged, Threaded};
 
-// /// Prelude module for creating worker.
-// pub mod worker {
-//     pub use agent::{
-//         Agent, AgentLink, Bridge, Bridged, Context, Global, HandlerId, Job, Private, Public,
-//         Transferable,
-//     };
-// }
+/// Prelude module for creating worker.
+pub mod worker {
+    pub use agent::{
+        Agent, AgentLink, Bridge, Bridged, Context, Global, HandlerId, Job, Private, Public,
+        Transferable,
+    };
+}
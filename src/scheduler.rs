@@ -2,8 +2,12 @@
 
 use std::cell::RefCell;
 use std::collections::VecDeque;
+use std::mem;
 use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::window;
 use Shared;
 
 thread_local! {
@@ -25,6 +29,10 @@ pub(crate) trait Runnable {
 pub(crate) struct Scheduler {
     lock: Rc<AtomicBool>,
     sequence: Shared<VecDeque<Box<dyn Runnable>>>,
+    /// Routines which run in reverse order once the sequence is empty.
+    after_render: Shared<Vec<Box<dyn Runnable>>>,
+    /// Renders postponed until the next animation frame with the mount order of their components.
+    frame: Shared<Vec<(usize, Box<dyn Runnable>)>>,
 }
 
 impl Clone for Scheduler {
@@ -32,6 +40,8 @@ impl Clone for Scheduler {
         Scheduler {
             lock: self.lock.clone(),
             sequence: self.sequence.clone(),
+            after_render: self.after_render.clone(),
+            frame: self.frame.clone(),
         }
     }
 }
@@ -43,6 +53,8 @@ impl Scheduler {
         Scheduler {
             lock: Rc::new(AtomicBool::new(false)),
             sequence: Rc::new(RefCell::new(sequence)),
+            after_render: Rc::new(RefCell::new(Vec::new())),
+            frame: Rc::new(RefCell::new(Vec::new())),
         }
     }
 
@@ -51,6 +63,7 @@ impl Scheduler {
         if self.lock.compare_and_swap(false, true, Ordering::Relaxed) == false {
             loop {
                 let do_next = self.sequence.borrow_mut().pop_front();
+                let do_next = do_next.or_else(|| self.after_render.borrow_mut().pop());
                 if let Some(mut runnable) = do_next {
                     runnable.run();
                 } else {
@@ -60,4 +73,50 @@ impl Scheduler {
             self.lock.store(false, Ordering::Relaxed);
         }
     }
+
+    /// Puts a routine which runs after all scheduled routines. These routines run
+    /// in reverse order, so ones put by children run before ones put by parents.
+    pub(crate) fn put_after_render(&self, runnable: Box<dyn Runnable>) {
+        self.after_render.borrow_mut().push(runnable);
+    }
+
+    /// Runs a routine right away, even if another one is running at the moment.
+    /// Routines put into the scheduler in the meantime still wait for their turn.
+    pub(crate) fn run_now(&self, mut runnable: Box<dyn Runnable>) {
+        if self.lock.load(Ordering::Relaxed) {
+            runnable.run();
+        } else {
+            self.put_and_try_run(runnable);
+        }
+    }
+
+    /// Postpones a render of a component until the next animation frame.
+    /// The `order` is the mount order of the component, so parents are rendered before children.
+    pub(crate) fn put_render(&self, order: usize, runnable: Box<dyn Runnable>) {
+        if self.queue_render(order, runnable) {
+            let callback = Closure::once_into_js(|| scheduler().flush_frame());
+            window()
+                .expect("batched renders need a window context")
+                .request_animation_frame(callback.unchecked_ref())
+                .expect("could not request an animation frame");
+        }
+    }
+
+    /// Queues a postponed render, returns `true` if it's the first one of the frame.
+    fn queue_render(&self, order: usize, runnable: Box<dyn Runnable>) -> bool {
+        let mut frame = self.frame.borrow_mut();
+        frame.push((order, runnable));
+        frame.len() == 1
+    }
+
+    /// Runs all postponed renders. Every render runs with the updates it causes
+    /// before the next one, so a child which was re-rendered by its parent has
+    /// nothing to render anymore.
+    fn flush_frame(&self) {
+        let mut renders = mem::take(&mut *self.frame.borrow_mut());
+        renders.sort_by_key(|&(order, _)| order);
+        for (_, runnable) in renders {
+            self.put_and_try_run(runnable);
+        }
+    }
 }
@@ -1,30 +1,58 @@
 //! This module contains the implementation of reactive virtual dom concept.
 
+pub mod style;
+pub mod vchildren;
 pub mod vcomp;
 pub mod vlist;
 pub mod vnode;
+pub mod vraw;
 pub mod vtag;
 pub mod vtext;
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt;
-use web_sys::{Element, Node};
+use wasm_bindgen::JsValue;
+use web_sys::{window, Element, Node};
 
+pub use self::style::Style;
+pub use self::vchildren::{Children, VChildren};
 pub use self::vcomp::VComp;
 pub use self::vlist::VList;
 pub use self::vnode::VNode;
+pub use self::vraw::VRaw;
 pub use self::vtag::VTag;
 pub use self::vtext::VText;
-use html::{Component, EventListenerHandle, Scope};
+use html::{Component, EventHandler, EventListenerHandle, Modifiers, Scope};
 
 /// `Listener` trait is an universal implementation of an event listener
 /// which helps to bind Rust-listener to JS-listener (DOM).
 pub trait Listener<COMP: Component> {
     /// Returns standard name of DOM's event.
     fn kind(&self) -> &'static str;
+    /// Returns the type of DOM events the listener handles (ie. "click").
+    fn event_type(&self) -> &'static str;
+    /// Returns modifiers which change how events are handled, the handler
+    /// applies `stop` and `prevent` itself.
+    fn modifiers(&self) -> Modifiers {
+        Modifiers::default()
+    }
+    /// Turns the listener into a handler of DOM events which uses scope instance
+    /// to send prepaired event back to the main loop.
+    fn handler(&mut self, element: &Element, scope: Scope<COMP>) -> EventHandler;
     /// Attaches listener to the element and uses scope instance to send
     /// prepaired event back to the yew main loop.
-    fn attach(&mut self, element: &Element, scope: Scope<COMP>) -> EventListenerHandle;
+    fn attach(&mut self, element: &Element, scope: Scope<COMP>) -> EventListenerHandle {
+        let event_type = self.event_type();
+        let modifiers = self.modifiers();
+        let delegator = scope.delegator();
+        let handler = self.handler(element, scope);
+        match delegator {
+            Some(ref delegator) if modifiers.delegable() && delegator.delegates(event_type) => {
+                delegator.register(element, handler, event_type, modifiers)
+            }
+            _ => EventListenerHandle::with_modifiers(element, handler, event_type, modifiers),
+        }
+    }
 }
 
 impl<COMP: Component> fmt::Debug for dyn Listener<COMP> {
@@ -39,6 +67,9 @@ type Listeners<COMP> = Vec<Box<dyn Listener<COMP>>>;
 /// A map of attributes.
 type Attributes = HashMap<String, String>;
 
+/// A map of JS properties of an element.
+type Properties = HashMap<String, JsValue>;
+
 /// A set of classes.
 type Classes = HashSet<String>;
 
@@ -106,4 +137,213 @@ pub trait VDiff {
         ancestor: Option<VNode<Self::Component>>,
         scope: &Scope<Self::Component>,
     ) -> Option<Node>;
+
+    /// Adopts an existing DOM node (for example rendered on a server) instead of creating
+    /// a new one. It's used for the first render of a hydrated `App`.
+    ///
+    /// Parameters:
+    /// - `parent`: the parent node in the DOM.
+    /// - `node`: the existing node which is expected to match this one,
+    ///   `None` if there are no nodes left in the `parent`.
+    /// - `scope`: the `Scope`.
+    ///
+    /// Returns the last DOM node claimed by this node, like `apply` does.
+    /// If the existing node doesn't match, a mismatch is logged and the node
+    /// is rendered in place of the existing one.
+    fn hydrate(
+        &mut self,
+        parent: &Node,
+        node: Option<Node>,
+        scope: &Scope<Self::Component>,
+    ) -> Option<Node>;
+}
+
+/// This trait provides rendering of a virtual tree to HTML markup.
+///
+/// Rendering doesn't touch the DOM, so it works on native targets too
+/// (to render pages on a server or to check the output of `view` in tests).
+pub trait VRender {
+    /// Writes the markup of the node to `out`.
+    fn render(&self, out: &mut String);
+
+    /// Renders the node to a string of HTML markup.
+    fn render_to_string(&self) -> String {
+        let mut out = String::new();
+        self.render(&mut out);
+        out
+    }
+}
+
+/// Writes text escaped for the content of an element.
+fn escape_text(text: &str, out: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            _ => out.push(c),
+        }
+    }
+}
+
+/// Writes text escaped for a double-quoted attribute value.
+fn escape_attribute(value: &str, out: &mut String) {
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '"' => out.push_str("&quot;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            _ => out.push(c),
+        }
+    }
+}
+
+/// Hydrates a list of children starting from the `first` existing node.
+/// Returns the last DOM node claimed by the children.
+fn hydrate_children<COMP: Component>(
+    parent: &Node,
+    first: Option<Node>,
+    children: &mut [VNode<COMP>],
+    env: &Scope<COMP>,
+) -> Option<Node> {
+    let mut last: Option<Node> = None;
+    for child in children.iter_mut() {
+        let next = match last {
+            Some(ref node) => node.next_sibling(),
+            None => first.clone(),
+        };
+        if let Some(node) = child.hydrate(parent, next, env) {
+            last = Some(node);
+        }
+    }
+    last
+}
+
+/// Renders a node which doesn't match the existing DOM node in place of it
+/// (or at the end of the `parent` if there is no existing node).
+fn hydrate_mismatch<T: VDiff>(
+    vnode: &mut T,
+    parent: &Node,
+    node: Option<Node>,
+    env: &Scope<T::Component>,
+) -> Option<Node> {
+    vnode.apply(parent, None, node.map(VNode::VRef), env)
+}
+
+/// Diffs a list of children against the children of an ancestor and applies the
+/// changes to the DOM. Returns the last DOM node of the list.
+///
+/// Children are paired by position unless at least one of the new children has
+/// a key. In that case keyed children are paired with the ancestor child that has
+/// the same key (moving its DOM nodes if necessary), and the remaining children
+/// are paired by position among themselves.
+fn diff_children<COMP: Component>(
+    parent: &Node,
+    precursor: Option<Node>,
+    lefts: &mut [VNode<COMP>],
+    rights: Vec<VNode<COMP>>,
+    env: &Scope<COMP>,
+) -> Option<Node> {
+    if lefts.iter().any(|node| node.key().is_some()) {
+        return diff_keyed_children(parent, precursor, lefts, rights, env);
+    }
+    let mut precursor = precursor;
+    let mut rights = rights.into_iter().map(Some).collect::<Vec<_>>();
+    let mut lefts = lefts.iter_mut().map(Some).collect::<Vec<_>>();
+    let diff = lefts.len() as i32 - rights.len() as i32;
+    if diff > 0 {
+        for _ in 0..diff {
+            rights.push(None);
+        }
+    } else if diff < 0 {
+        for _ in 0..-diff {
+            lefts.push(None);
+        }
+    }
+    for pair in lefts.into_iter().zip(rights) {
+        match pair {
+            (Some(left), right) => {
+                precursor = left.apply(parent, precursor.as_ref(), right, env);
+            }
+            (None, Some(mut right)) => {
+                right.detach(parent);
+            }
+            (None, None) => {
+                panic!("redundant iterations during diff");
+            }
+        }
+    }
+    precursor
+}
+
+/// Keyed variant of `diff_children`.
+fn diff_keyed_children<COMP: Component>(
+    parent: &Node,
+    precursor: Option<Node>,
+    lefts: &mut [VNode<COMP>],
+    rights: Vec<VNode<COMP>>,
+    env: &Scope<COMP>,
+) -> Option<Node> {
+    // Without a precursor the list starts where the first previously rendered
+    // child is, so it has to be found before anything is moved.
+    let start = match precursor {
+        Some(_) => None,
+        None => rights.first().and_then(VNode::first_node),
+    };
+    let mut keyed = HashMap::new();
+    let mut unkeyed = VecDeque::new();
+    for right in rights {
+        let key = right.key().map(str::to_owned);
+        match key {
+            Some(key) => {
+                if let Some(mut duplicate) = keyed.insert(key, right) {
+                    duplicate.detach(parent);
+                }
+            }
+            None => unkeyed.push_back(right),
+        }
+    }
+    let mut last = precursor;
+    for left in lefts.iter_mut() {
+        let right = match left.key() {
+            Some(key) => keyed.remove(key),
+            None => unkeyed.pop_front(),
+        };
+        // The DOM node which has to follow this child.
+        let next = match last {
+            Some(ref node) => node.next_sibling(),
+            None => start.clone(),
+        };
+        let right = match right {
+            Some(right) => {
+                right.move_before(parent, next.as_ref());
+                Some(right)
+            }
+            // A new child is rendered over an empty text node that stakes out
+            // its place, because `apply` can only insert after a precursor.
+            None => next.map(|next| {
+                let placeholder: Node = window()
+                    .expect("context needs a window")
+                    .document()
+                    .expect("window needs a document")
+                    .create_text_node("")
+                    .into();
+                parent
+                    .insert_before(&placeholder, Some(&next))
+                    .expect("can't insert placeholder for a keyed child");
+                VNode::VRef(placeholder)
+            }),
+        };
+        if let Some(node) = left.apply(parent, last.as_ref(), right, env) {
+            last = Some(node);
+        }
+    }
+    for (_, mut right) in keyed {
+        right.detach(parent);
+    }
+    for mut right in unkeyed {
+        right.detach(parent);
+    }
+    last
 }
@@ -0,0 +1,173 @@
+//! This module contains `Style`, the inline style of a `VTag` which is
+//! diffed and applied property by property.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// Properties of an inline style (ie. `style="color: red; --gap: 4px"`).
+///
+/// It could be built from a string, a map, a list of pairs or with the builder:
+///
+/// ```
+/// # use plaster::virtual_dom::Style;
+/// let style = Style::new().with("color", "red").with("--gap", "4px");
+/// assert_eq!(style, Style::from("color: red; --gap: 4px"));
+/// ```
+///
+/// Properties keep the order they were set in, because shorthand properties
+/// (like `margin`) and longhand ones (like `margin-top`) override each other.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Style {
+    properties: Vec<(String, String)>,
+}
+
+impl Style {
+    /// Creates an empty style.
+    pub fn new() -> Self {
+        Style::default()
+    }
+
+    /// Sets the property and returns the style, to build styles in templates.
+    pub fn with<N: Into<String>, V: ToString>(mut self, name: N, value: V) -> Self {
+        self.set(name, value);
+        self
+    }
+
+    /// Sets the property, a value could end with `!important`.
+    pub fn set<N: Into<String>, V: ToString>(&mut self, name: N, value: V) {
+        let name = name.into();
+        let value = value.to_string();
+        match self.properties.iter_mut().find(|(other, _)| *other == name) {
+            Some(property) => property.1 = value,
+            None => self.properties.push((name, value)),
+        }
+    }
+
+    /// Removes the property.
+    pub fn remove(&mut self, name: &str) {
+        self.properties.retain(|(other, _)| other != name);
+    }
+
+    /// Returns the value of the property.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.properties
+            .iter()
+            .find(|(other, _)| other == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns `true` if the style has no properties.
+    pub fn is_empty(&self) -> bool {
+        self.properties.is_empty()
+    }
+
+    /// Iterates over names and values of properties in the order they were set.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.properties
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+}
+
+/// Splits a declaration block at semicolons which are not in quotes or parentheses
+/// (ie. `background: url("a;b.png")`).
+fn declarations(css: &str) -> Vec<&str> {
+    let mut declarations = Vec::new();
+    let mut quote = None;
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (index, ch) in css.char_indices() {
+        match (quote, ch) {
+            (Some(open), ch) if ch == open => quote = None,
+            (Some(_), _) => {}
+            (None, '"') | (None, '\'') => quote = Some(ch),
+            (None, '(') => depth += 1,
+            (None, ')') => depth = depth.saturating_sub(1),
+            (None, ';') if depth == 0 => {
+                declarations.push(&css[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    declarations.push(&css[start..]);
+    declarations
+}
+
+impl<'a> From<&'a str> for Style {
+    fn from(css: &'a str) -> Self {
+        let mut style = Style::new();
+        for declaration in declarations(css) {
+            if declaration.trim().is_empty() {
+                continue;
+            }
+            match declaration.find(':') {
+                Some(colon) => {
+                    let name = declaration[..colon].trim();
+                    let value = declaration[colon + 1..].trim();
+                    style.set(name, value);
+                }
+                None => warn!("ignored a declaration without a value: {}", declaration),
+            }
+        }
+        style
+    }
+}
+
+impl From<String> for Style {
+    fn from(css: String) -> Self {
+        Style::from(css.as_str())
+    }
+}
+
+impl<'a> From<&'a String> for Style {
+    fn from(css: &'a String) -> Self {
+        Style::from(css.as_str())
+    }
+}
+
+impl<N: Into<String>, V: ToString> From<Vec<(N, V)>> for Style {
+    fn from(properties: Vec<(N, V)>) -> Self {
+        properties.into_iter().collect()
+    }
+}
+
+impl<N: Into<String>, V: ToString> From<HashMap<N, V>> for Style {
+    fn from(properties: HashMap<N, V>) -> Self {
+        let mut properties = properties
+            .into_iter()
+            .map(|(name, value)| (name.into(), value))
+            .collect::<Vec<(String, V)>>();
+        // Hash maps have no order, sort properties to get a stable style
+        properties.sort_by(|a, b| a.0.cmp(&b.0));
+        properties.into_iter().collect()
+    }
+}
+
+impl<N: Into<String>, V: ToString> From<BTreeMap<N, V>> for Style {
+    fn from(properties: BTreeMap<N, V>) -> Self {
+        properties.into_iter().collect()
+    }
+}
+
+impl<N: Into<String>, V: ToString> ::std::iter::FromIterator<(N, V)> for Style {
+    fn from_iter<I: IntoIterator<Item = (N, V)>>(iter: I) -> Self {
+        let mut style = Style::new();
+        for (name, value) in iter {
+            style.set(name, value);
+        }
+        style
+    }
+}
+
+impl fmt::Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, (name, value)) in self.iter().enumerate() {
+            if index > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{}: {};", name, value)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,382 @@
+//! This module contains `Children` which pass markup of a parent component
+//! to a child component and `VChildren` which renders it inside the child.
+
+use super::{VDiff, VNode, VRender};
+use context::Context;
+use html::{Component, Scope};
+use std::any::Any;
+use std::fmt;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use web_sys::{window, Node};
+use Shared;
+
+/// Counter of ids of `VChildren` nodes.
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// A reference to the scope of the parent which will be attached when the parent
+/// renders the child component.
+pub(crate) type ParentActivator<COMP> = Shared<Option<Scope<COMP>>>;
+
+/// Where a tree of children is rendered to.
+enum Ancestor {
+    /// The tree which was passed to the child before.
+    Tree(Box<dyn Tree>),
+    /// An empty text node which stakes out the place of the tree.
+    Placeholder(Node),
+}
+
+/// A tree of a parent component with its type hidden from the child component.
+trait Tree {
+    fn apply(
+        &mut self,
+        parent: &Node,
+        precursor: Option<&Node>,
+        ancestor: Ancestor,
+        context: Rc<Context>,
+    ) -> Option<Node>;
+    fn hydrate(&mut self, parent: &Node, node: Option<Node>, context: Rc<Context>) -> Option<Node>;
+    fn detach(&mut self, parent: &Node) -> Option<Node>;
+    fn first_node(&self) -> Option<Node>;
+    fn move_before(&self, parent: &Node, next: Option<&Node>);
+    fn destroy_components(&mut self);
+    fn set_render_context(&mut self, context: &Rc<Context>);
+    fn render(&self, out: &mut String);
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+/// Markup rendered by the parent component.
+struct ParentTree<COMP: Component> {
+    node: VNode<COMP>,
+    activator: ParentActivator<COMP>,
+}
+
+impl<COMP: Component> ParentTree<COMP> {
+    /// Returns the scope of the parent which inherits values of the child's context,
+    /// so nested components see values provided by the child.
+    fn env(&self, context: Rc<Context>) -> Scope<COMP> {
+        self.activator
+            .borrow()
+            .clone()
+            .expect("children were not activated by the parent component")
+            .with_context(context)
+    }
+}
+
+impl<COMP: Component> Tree for ParentTree<COMP> {
+    fn apply(
+        &mut self,
+        parent: &Node,
+        precursor: Option<&Node>,
+        ancestor: Ancestor,
+        context: Rc<Context>,
+    ) -> Option<Node> {
+        let ancestor = match ancestor {
+            Ancestor::Tree(tree) => {
+                let tree = tree
+                    .into_any()
+                    .downcast::<ParentTree<COMP>>()
+                    .expect("children of a component have to come from the same parent");
+                tree.node
+            }
+            Ancestor::Placeholder(node) => VNode::VRef(node),
+        };
+        let env = self.env(context);
+        self.node.apply(parent, precursor, Some(ancestor), &env)
+    }
+
+    fn hydrate(&mut self, parent: &Node, node: Option<Node>, context: Rc<Context>) -> Option<Node> {
+        let env = self.env(context);
+        self.node.hydrate(parent, node, &env)
+    }
+
+    fn detach(&mut self, parent: &Node) -> Option<Node> {
+        self.node.detach(parent)
+    }
+
+    fn first_node(&self) -> Option<Node> {
+        self.node.first_node()
+    }
+
+    fn move_before(&self, parent: &Node, next: Option<&Node>) {
+        self.node.move_before(parent, next);
+    }
+
+    fn destroy_components(&mut self) {
+        self.node.destroy_components();
+    }
+
+    fn set_render_context(&mut self, context: &Rc<Context>) {
+        self.node.set_render_context(context);
+    }
+
+    fn render(&self, out: &mut String) {
+        self.node.render(out);
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+#[derive(Default)]
+struct Inner {
+    tree: Option<Box<dyn Tree>>,
+    /// The id of the `VChildren` node which has the tree in the DOM.
+    owner: Option<usize>,
+}
+
+/// Markup which a parent component passes to a child component between its
+/// opening and closing tags (ie. `<Card: title="Hello",><p>{ "Text" }</p></Card>`).
+///
+/// The child keeps it in the `children` field of its properties and renders
+/// it in `view` with `{ self.props.children.clone() }`. Listeners of the markup
+/// send messages to the parent. The markup could be rendered once, if a view
+/// renders it at another place, it's moved there.
+#[derive(Clone, Default)]
+pub struct Children(Shared<Inner>);
+
+impl Children {
+    /// Returns `true` if the parent passed no markup.
+    pub fn is_empty(&self) -> bool {
+        self.0.borrow().tree.is_none()
+    }
+
+    /// Fills in the markup of the parent.
+    pub(crate) fn set<COMP: Component>(&self, node: VNode<COMP>, activator: ParentActivator<COMP>) {
+        let tree = ParentTree { node, activator };
+        self.0.borrow_mut().tree = Some(Box::new(tree));
+    }
+}
+
+impl PartialEq for Children {
+    fn eq(&self, other: &Children) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl fmt::Debug for Children {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Children {{ empty: {} }}", self.is_empty())
+    }
+}
+
+/// A virtual node which renders `Children` of a component.
+pub struct VChildren {
+    id: usize,
+    children: Children,
+    /// An empty text node which stakes out the place of empty children.
+    placeholder: Option<Node>,
+}
+
+impl VChildren {
+    /// Creates a node which renders the children.
+    pub fn new(children: Children) -> Self {
+        VChildren {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            children,
+            placeholder: None,
+        }
+    }
+
+    /// Returns `true` if the children are rendered by this node.
+    fn owns(&self) -> bool {
+        self.placeholder.is_some() || self.children.0.borrow().owner == Some(self.id)
+    }
+
+    /// Returns the first DOM node of the rendered children.
+    pub(crate) fn first_node(&self) -> Option<Node> {
+        if self.placeholder.is_some() {
+            return self.placeholder.clone();
+        }
+        let inner = self.children.0.borrow();
+        match inner.tree {
+            Some(ref tree) if inner.owner == Some(self.id) => tree.first_node(),
+            _ => None,
+        }
+    }
+
+    /// Moves the DOM nodes of the rendered children before `next`.
+    pub(crate) fn move_before(&self, parent: &Node, next: Option<&Node>) {
+        if let Some(ref placeholder) = self.placeholder {
+            parent
+                .insert_before(placeholder, next)
+                .expect("can't move placeholder of children");
+            return;
+        }
+        let inner = self.children.0.borrow();
+        if let Some(ref tree) = inner.tree {
+            if inner.owner == Some(self.id) {
+                tree.move_before(parent, next);
+            }
+        }
+    }
+
+    /// Destroys components of the children if they are rendered by this node.
+    pub(crate) fn destroy_components(&mut self) {
+        let mut inner = self.children.0.borrow_mut();
+        let owner = inner.owner;
+        if let Some(ref mut tree) = inner.tree {
+            if owner == Some(self.id) {
+                tree.destroy_components();
+            }
+        }
+    }
+
+    /// Sets the context of the child component which renders the children to a string.
+    pub(crate) fn set_render_context(&mut self, context: &Rc<Context>) {
+        if let Some(ref mut tree) = self.children.0.borrow_mut().tree {
+            tree.set_render_context(context);
+        }
+    }
+
+    /// Removes the children from the DOM if they are rendered by this node.
+    pub(crate) fn detach(&mut self, parent: &Node) -> Option<Node> {
+        if let Some(placeholder) = self.placeholder.take() {
+            let sibling = placeholder.next_sibling();
+            parent
+                .remove_child(&placeholder)
+                .expect("can't remove placeholder of children");
+            return sibling;
+        }
+        let mut inner = self.children.0.borrow_mut();
+        if inner.owner != Some(self.id) {
+            return None;
+        }
+        inner.owner = None;
+        inner.tree.as_mut().and_then(|tree| tree.detach(parent))
+    }
+
+    /// Renders the children in place of the ancestor.
+    pub(crate) fn apply<COMP: Component>(
+        &mut self,
+        parent: &Node,
+        precursor: Option<&Node>,
+        ancestor: Option<VNode<COMP>>,
+        env: &Scope<COMP>,
+    ) -> Option<Node> {
+        let ancestor = match ancestor {
+            Some(VNode::VChildren(mut old)) => {
+                if old.children == self.children && old.owns() {
+                    // The child is rendered again with the same children
+                    self.placeholder = old.placeholder.take();
+                    if self.placeholder.is_none() {
+                        self.children.0.borrow_mut().owner = Some(self.id);
+                    }
+                    return self.first_node();
+                }
+                let diffable = {
+                    let inner = self.children.0.borrow();
+                    inner.tree.is_some() && inner.owner.is_none()
+                };
+                let tree = if diffable && old.placeholder.is_none() {
+                    let mut inner = old.children.0.borrow_mut();
+                    if inner.owner == Some(old.id) {
+                        inner.owner = None;
+                        inner.tree.take()
+                    } else {
+                        None
+                    }
+                } else {
+                    None
+                };
+                match tree {
+                    Some(tree) => Ancestor::Tree(tree),
+                    None => {
+                        let sibling = old.detach(parent);
+                        Ancestor::Placeholder(placeholder(parent, precursor, sibling))
+                    }
+                }
+            }
+            Some(mut node) => {
+                let sibling = node.detach(parent);
+                Ancestor::Placeholder(placeholder(parent, precursor, sibling))
+            }
+            None => Ancestor::Placeholder(placeholder(parent, precursor, None)),
+        };
+        self.mount(parent, precursor, ancestor, env)
+    }
+
+    /// Adopts the existing node for the children.
+    pub(crate) fn hydrate<COMP: Component>(
+        &mut self,
+        parent: &Node,
+        node: Option<Node>,
+        env: &Scope<COMP>,
+    ) -> Option<Node> {
+        {
+            let mut inner = self.children.0.borrow_mut();
+            if inner.owner.is_none() {
+                if let Some(ref mut tree) = inner.tree {
+                    let node = tree.hydrate(parent, node, env.context());
+                    inner.owner = Some(self.id);
+                    return node;
+                }
+            }
+        }
+        // Empty children aren't rendered to markup, so there is no node to adopt
+        let placeholder = placeholder(parent, None, node);
+        self.mount(parent, None, Ancestor::Placeholder(placeholder), env)
+    }
+
+    /// Renders the tree of the children over the ancestor.
+    fn mount<COMP: Component>(
+        &mut self,
+        parent: &Node,
+        precursor: Option<&Node>,
+        ancestor: Ancestor,
+        env: &Scope<COMP>,
+    ) -> Option<Node> {
+        let mut inner = self.children.0.borrow_mut();
+        let mounted = inner.owner.is_some();
+        let tree = match (inner.tree.as_mut(), ancestor) {
+            (None, Ancestor::Placeholder(node)) => {
+                // There is nothing to render, the empty text node stays in the DOM
+                self.placeholder = Some(node.clone());
+                return Some(node);
+            }
+            (None, Ancestor::Tree(_)) => return None,
+            (Some(tree), Ancestor::Placeholder(ref node)) if mounted => {
+                // The children are rendered at another place of the view, they are moved here
+                tree.move_before(parent, Some(node));
+                parent
+                    .remove_child(node)
+                    .expect("can't remove placeholder of children");
+                tree.first_node()
+            }
+            (Some(tree), ancestor) => tree.apply(parent, precursor, ancestor, env.context()),
+        };
+        inner.owner = Some(self.id);
+        tree
+    }
+}
+
+impl VRender for VChildren {
+    fn render(&self, out: &mut String) {
+        if let Some(ref tree) = self.children.0.borrow().tree {
+            tree.render(out);
+        }
+    }
+}
+
+impl fmt::Debug for VChildren {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "VChildren {{ id: {} }}", self.id)
+    }
+}
+
+/// Inserts an empty text node before `sibling`, or after `precursor` if there is
+/// no sibling, to stake out a place for the children.
+fn placeholder(parent: &Node, precursor: Option<&Node>, sibling: Option<Node>) -> Node {
+    let placeholder: Node = window()
+        .expect("context needs a window")
+        .document()
+        .expect("window needs a document")
+        .create_text_node("")
+        .into();
+    let next = sibling.or_else(|| precursor.and_then(|node| node.next_sibling()));
+    parent
+        .insert_before(&placeholder, next.as_ref())
+        .expect("can't insert placeholder for children");
+    placeholder
+}
@@ -1,9 +1,10 @@
 //! This module contains the implementation of a virtual component `VComp`.
 
-use super::{Reform, VDiff, VNode};
+use super::{Children, Reform, VDiff, VList, VNode, VRender};
 use callback::Callback;
+use context::Context;
 use html::{Component, ComponentUpdate, NodeCell, Renderable, Scope};
-use std::any::{Any, TypeId};
+use std::any::{type_name, Any, TypeId};
 use std::cell::RefCell;
 use std::marker::PhantomData;
 use std::rc::Rc;
@@ -13,21 +14,50 @@ use Hidden;
 
 type AnyProps = (TypeId, *mut Hidden);
 
-/// The method generates an instance of a (child) component.
-type Generator = dyn FnMut(Element, Node, AnyProps);
+/// The method generates an instance of a (child) component within the scope of its parent.
+/// The flag tells if the component has to hydrate the node instead of replacing it.
+type Generator<COMP> = dyn FnMut(Element, Node, AnyProps, &Scope<COMP>, bool);
+
+/// The method renders an instance of a (child) component to HTML markup
+/// within the context of its parent.
+type Renderer = dyn Fn(&AnyProps, Option<Rc<Context>>) -> String;
 
 /// A reference to unknown activator which will be attached later with a generator function.
 type LazyActivator<COMP> = Rc<RefCell<Option<Scope<COMP>>>>;
 
+/// The rendered view of a child component with its type hidden from the parent.
+trait View {
+    fn first_node(&self) -> Option<Node>;
+    fn move_before(&self, parent: &Node, next: Option<&Node>);
+}
+
+impl<CHILD: Component> View for LazyActivator<CHILD> {
+    fn first_node(&self) -> Option<Node> {
+        self.borrow().as_ref().and_then(Scope::first_node)
+    }
+
+    fn move_before(&self, parent: &Node, next: Option<&Node>) {
+        if let Some(ref scope) = *self.borrow() {
+            scope.move_before(parent, next);
+        }
+    }
+}
+
 /// A virtual component.
 pub struct VComp<COMP: Component> {
     type_id: TypeId,
+    type_name: &'static str,
+    key: Option<String>,
     cell: NodeCell,
     props: Option<(TypeId, *mut Hidden)>,
     blind_sender: Box<dyn FnMut(AnyProps)>,
-    generator: Box<Generator>,
+    generator: Box<Generator<COMP>>,
+    renderer: Box<Renderer>,
+    render_context: Option<Rc<Context>>,
     activators: Vec<LazyActivator<COMP>>,
+    children: Option<Children>,
     destroyer: Box<dyn Fn()>,
+    view: Rc<dyn View>,
     _parent: PhantomData<COMP>,
 }
 
@@ -43,7 +73,11 @@ impl<COMP: Component> VComp<COMP> {
         // This function creates and mounts a new component instance
         let generator = {
             let lazy_activator = lazy_activator.clone();
-            move |element, ancestor: Node, (type_id, raw): AnyProps| {
+            move |element,
+                  ancestor: Node,
+                  (type_id, raw): AnyProps,
+                  parent: &Scope<COMP>,
+                  hydrate: bool| {
                 if type_id != TypeId::of::<CHILD>() {
                     panic!("tried to unpack properties of the other component");
                 }
@@ -51,18 +85,37 @@ impl<COMP: Component> VComp<COMP> {
                     let raw: *mut CHILD::Properties = ::std::mem::transmute(raw);
                     *Box::from_raw(raw)
                 };
-                let scope: Scope<CHILD> = Scope::new();
+                let scope: Scope<CHILD> = Scope::new().with_parent(parent);
                 let env = scope.clone();
                 *lazy_activator.borrow_mut() = Some(env);
-                scope.mount_in_place(
-                    element,
-                    Some(VNode::VRef(ancestor)),
-                    Some(occupied.clone()),
-                    Some(props),
-                );
+                if hydrate {
+                    scope.hydrate_in_place(
+                        element,
+                        Some(ancestor),
+                        Some(occupied.clone()),
+                        Some(props),
+                    );
+                } else {
+                    scope.mount_in_place(
+                        element,
+                        Some(VNode::VRef(ancestor)),
+                        Some(occupied.clone()),
+                        Some(props),
+                    );
+                }
                 // TODO Consider to send ComponentUpdate::Create after `mount_in_place` call
             }
         };
+        // This function creates a detached instance to render it to a string
+        let renderer = |&(type_id, raw): &AnyProps, context: Option<Rc<Context>>| {
+            if type_id != TypeId::of::<CHILD>() {
+                panic!("tried to render properties of the other component");
+            }
+            // Properties are cloned, because they are still needed to mount the component
+            let props = unsafe { (*(raw as *mut CHILD::Properties)).clone() };
+            let scope: Scope<CHILD> = Scope::new().with_parent_context(context);
+            scope.render_to_string(Some(props))
+        };
         let blind_sender = {
             let mut previous_props = None;
             let lazy_activator = lazy_activator.clone();
@@ -91,6 +144,7 @@ impl<COMP: Component> VComp<COMP> {
                 }
             }
         };
+        let view = Rc::new(lazy_activator.clone());
         let destroyer = {
             let lazy_activator = lazy_activator;
             move || {
@@ -104,12 +158,18 @@ impl<COMP: Component> VComp<COMP> {
         let properties = Default::default();
         let comp = VComp {
             type_id: TypeId::of::<CHILD>(),
+            type_name: type_name::<CHILD>(),
+            key: None,
             cell,
             props: None,
             blind_sender: Box::new(blind_sender),
             generator: Box::new(generator),
+            renderer: Box::new(renderer),
+            render_context: None,
             activators: Vec::new(),
+            children: None,
             destroyer: Box::new(destroyer),
+            view,
             _parent: PhantomData,
         };
         (properties, comp)
@@ -122,6 +182,63 @@ impl<COMP: Component> VComp<COMP> {
         self.props = Some((self.type_id, data));
     }
 
+    /// Sets the key of the component. Keyed components keep their state when
+    /// they are moved around in a list of siblings.
+    pub fn set_key<T: ToString>(&mut self, key: &T) {
+        self.key = Some(key.to_string());
+    }
+
+    /// Returns the children of the component which are filled in when its tag is closed.
+    pub fn children(&mut self) -> Children {
+        let children = Children::default();
+        self.children = Some(children.clone());
+        children
+    }
+
+    /// Fills in the children of the component with the markup between its tags.
+    pub fn set_children(&mut self, vlist: VList<COMP>) {
+        let activator = Rc::new(RefCell::new(None));
+        self.activators.push(activator.clone());
+        self.children
+            .take()
+            .expect("component has no children to fill in")
+            .set(vlist.into(), activator);
+    }
+
+    /// Returns the name of the component type without its path and type parameters.
+    pub(crate) fn name(&self) -> &'static str {
+        let path = self.type_name.split('<').next().unwrap_or(self.type_name);
+        path.rsplit("::").next().unwrap_or(path)
+    }
+
+    /// Returns the key of the component.
+    pub fn key(&self) -> Option<&str> {
+        self.key.as_deref()
+    }
+
+    /// Returns the DOM node of the mounted component.
+    pub(crate) fn node(&self) -> Option<Node> {
+        self.cell.borrow().as_ref().map(|node| node.to_owned())
+    }
+
+    /// Returns the first DOM node of the mounted component, a component which
+    /// renders a list has several nodes and `node` is the last one of them.
+    pub(crate) fn first_node(&self) -> Option<Node> {
+        self.view.first_node().or_else(|| self.node())
+    }
+
+    /// Moves all DOM nodes of the mounted component before `next`.
+    pub(crate) fn move_before(&self, parent: &Node, next: Option<&Node>) {
+        if self.view.first_node().is_some() {
+            self.view.move_before(parent, next);
+        } else if let Some(node) = self.node() {
+            // The component isn't created yet, only its placeholder is there
+            parent
+                .insert_before(&node, next)
+                .expect("can't move the component");
+        }
+    }
+
     /// This method attach sender to a listeners, because created properties
     /// know nothing about a parent.
     fn activate_props(&mut self, sender: &Scope<COMP>) -> AnyProps {
@@ -133,6 +250,16 @@ impl<COMP: Component> VComp<COMP> {
             .expect("tried to activate properties twice")
     }
 
+    /// Sets the context of the parent to render the component to a string.
+    pub(crate) fn set_render_context(&mut self, context: &Rc<Context>) {
+        self.render_context = Some(context.clone());
+    }
+
+    /// Destroys the component without removing its node.
+    pub(crate) fn destroy(&mut self) {
+        (self.destroyer)();
+    }
+
     /// This methods gives sender from older node.
     pub(crate) fn grab_sender_of(&mut self, other: Self) {
         assert_eq!(self.type_id, other.type_id);
@@ -140,6 +267,7 @@ impl<COMP: Component> VComp<COMP> {
         self.cell = other.cell;
         self.blind_sender = other.blind_sender;
         self.destroyer = other.destroyer;
+        self.view = other.view;
     }
 }
 
@@ -214,12 +342,14 @@ where
         parent: &Node,
         ancestor: Node, // Any dummy expected
         props: AnyProps,
+        env: &Scope<COMP>,
+        hydrate: bool,
     ) {
         let element: Element = parent
             .to_owned()
             .dyn_into()
             .expect("element expected to mount VComp");
-        (self.generator)(element, ancestor, props);
+        (self.generator)(element, ancestor, props, env, hydrate);
     }
 
     fn send_props(&mut self, props: AnyProps) {
@@ -308,15 +438,47 @@ where
                             .expect("could not append child to element");
                     }
                 }
-                self.mount(parent, element.into(), any_props);
+                let dummy: Node = element.into();
+                self.mount(parent, dummy.clone(), any_props, env, false);
+                // The component is mounted by the scheduler later, until then the dummy
+                // node holds its place and is the precursor of the next sibling.
+                return self.node().or(Some(dummy));
             }
         }
-        self.cell.borrow().as_ref().map(|node| node.to_owned())
+        self.node()
+    }
+
+    /// Creates the component right away and lets it hydrate the existing node.
+    fn hydrate(
+        &mut self,
+        parent: &Node,
+        node: Option<Node>,
+        env: &Scope<Self::Component>,
+    ) -> Option<Node> {
+        match node {
+            Some(node) => {
+                let any_props = self.activate_props(env);
+                self.mount(parent, node, any_props, env, true);
+                self.node()
+            }
+            // There is nothing to adopt, so the component is mounted as usual
+            None => self.apply(parent, None, None, env),
+        }
+    }
+}
+
+impl<COMP: Component> VRender for VComp<COMP> {
+    fn render(&self, out: &mut String) {
+        let props = self
+            .props
+            .as_ref()
+            .expect("tried to render a component without properties");
+        out.push_str(&(self.renderer)(props, self.render_context.clone()));
     }
 }
 
 impl<COMP: Component> PartialEq for VComp<COMP> {
     fn eq(&self, other: &VComp<COMP>) -> bool {
-        self.type_id == other.type_id
+        self.type_id == other.type_id && self.key == other.key
     }
 }
@@ -1,5 +1,5 @@
 //! This module contains fragments implementation.
-use super::{VDiff, VNode, VText};
+use super::{diff_children, hydrate_children, VDiff, VNode, VRender, VText};
 use html::{Component, Scope};
 use std::iter::FromIterator;
 use web_sys::Node;
@@ -59,24 +59,22 @@ impl<COMP: Component> VDiff for VList<COMP> {
         env: &Scope<Self::Component>,
     ) -> Option<Node> {
         // Reuse precursor, because fragment reuse parent
-        let mut precursor = precursor.map(|node| node.to_owned());
-        let mut rights = {
+        let precursor = precursor.map(|node| node.to_owned());
+        let rights = {
             match ancestor {
                 // If element matched this type
                 Some(VNode::VList(mut vlist)) => {
                     // Previously rendered items
-                    vlist.childs.drain(..).map(Some).collect::<Vec<_>>()
+                    vlist.childs.drain(..).collect::<Vec<_>>()
                 }
-                Some(mut vnode) => {
+                Some(vnode) => {
                     // Use the current node as a single fragment list
                     // and let the `apply` of `VNode` to handle it.
-                    vec![Some(vnode)]
+                    vec![vnode]
                 }
                 None => Vec::new(),
             }
         };
-        // Collect elements of an ancestor if exists or use an empty vec
-        // TODO DRY?!
         if self.childs.is_empty() {
             // Fixes: https://github.com/DenisKolodin/yew/issues/294
             // Without a placeholder the next element becomes first
@@ -85,32 +83,29 @@ impl<COMP: Component> VDiff for VList<COMP> {
             let placeholder = VText::new("".into());
             self.childs.push(placeholder.into());
         }
-        let mut lefts = self.childs.iter_mut().map(Some).collect::<Vec<_>>();
-        // Process children
-        let diff = lefts.len() as i32 - rights.len() as i32;
-        if diff > 0 {
-            for _ in 0..diff {
-                rights.push(None);
-            }
-        } else if diff < 0 {
-            for _ in 0..-diff {
-                lefts.push(None);
-            }
+        diff_children(parent, precursor, &mut self.childs, rights, env)
+    }
+
+    fn hydrate(
+        &mut self,
+        parent: &Node,
+        node: Option<Node>,
+        env: &Scope<Self::Component>,
+    ) -> Option<Node> {
+        if self.childs.is_empty() {
+            // The same placeholder as in `apply`
+            let placeholder = VText::new("".into());
+            self.childs.push(placeholder.into());
         }
-        for pair in lefts.into_iter().zip(rights) {
-            match pair {
-                (Some(left), right) => {
-                    precursor = left.apply(parent, precursor.as_ref(), right, &env);
-                }
-                (None, Some(mut right)) => {
-                    right.detach(parent);
-                }
-                (None, None) => {
-                    panic!("redundant iterations during diff");
-                }
-            }
+        hydrate_children(parent, node, &mut self.childs, env)
+    }
+}
+
+impl<COMP: Component> VRender for VList<COMP> {
+    fn render(&self, out: &mut String) {
+        for child in &self.childs {
+            child.render(out);
         }
-        precursor
     }
 }
 
@@ -1,10 +1,13 @@
 //! This module contains the implementation of abstract virtual node.
 
-use super::{VComp, VDiff, VList, VTag, VText};
+use super::{escape_text, Children, VChildren, VComp, VDiff, VList, VRaw, VRender, VTag, VText};
+use context::Context;
 use html::{Component, Renderable, Scope};
 use std::cmp::PartialEq;
 use std::fmt;
-use web_sys::Node;
+use std::rc::Rc;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, Node};
 
 /// Bind virtual element to a DOM reference.
 pub enum VNode<COMP: Component> {
@@ -18,6 +21,95 @@ pub enum VNode<COMP: Component> {
     VList(VList<COMP>),
     /// A holder for any `Node` (necessary for replacing node).
     VRef(Node),
+    /// Markup passed to the component by its parent.
+    VChildren(VChildren),
+    /// A bind between raw HTML markup and its container `Element`.
+    VRaw(VRaw<COMP>),
+}
+
+impl<COMP: Component> VNode<COMP> {
+    /// Returns the key of the node if it is a keyed `VTag` or `VComp`.
+    pub fn key(&self) -> Option<&str> {
+        match *self {
+            VNode::VTag(ref vtag) => vtag.key.as_deref(),
+            VNode::VComp(ref vcomp) => vcomp.key(),
+            _ => None,
+        }
+    }
+
+    /// Returns the first DOM node of a rendered node.
+    pub(crate) fn first_node(&self) -> Option<Node> {
+        match *self {
+            VNode::VTag(ref vtag) => vtag.reference.as_ref().map(|e| e.to_owned().into()),
+            VNode::VText(ref vtext) => vtext.reference.as_ref().map(|t| t.to_owned().into()),
+            VNode::VComp(ref vcomp) => vcomp.first_node(),
+            VNode::VList(ref vlist) => vlist.childs.first().and_then(VNode::first_node),
+            VNode::VRef(ref node) => Some(node.to_owned()),
+            VNode::VChildren(ref vchildren) => vchildren.first_node(),
+            VNode::VRaw(ref vraw) => vraw.reference.as_ref().map(|e| e.to_owned().into()),
+        }
+    }
+
+    /// Destroys nested components without touching the DOM,
+    /// because the whole tree is removed at once.
+    pub(crate) fn destroy_components(&mut self) {
+        match *self {
+            VNode::VTag(ref mut vtag) => {
+                for child in &mut vtag.childs {
+                    child.destroy_components();
+                }
+            }
+            VNode::VList(ref mut vlist) => {
+                for child in &mut vlist.childs {
+                    child.destroy_components();
+                }
+            }
+            VNode::VComp(ref mut vcomp) => vcomp.destroy(),
+            VNode::VChildren(ref mut vchildren) => vchildren.destroy_components(),
+            VNode::VText(_) | VNode::VRef(_) | VNode::VRaw(_) => {}
+        }
+    }
+
+    /// Sets the context of the component which renders the node to a string,
+    /// so nested components inherit values it provides.
+    pub(crate) fn set_render_context(&mut self, context: &Rc<Context>) {
+        match *self {
+            VNode::VTag(ref mut vtag) => {
+                for child in &mut vtag.childs {
+                    child.set_render_context(context);
+                }
+            }
+            VNode::VList(ref mut vlist) => {
+                for child in &mut vlist.childs {
+                    child.set_render_context(context);
+                }
+            }
+            VNode::VComp(ref mut vcomp) => vcomp.set_render_context(context),
+            VNode::VChildren(ref mut vchildren) => vchildren.set_render_context(context),
+            VNode::VText(_) | VNode::VRef(_) | VNode::VRaw(_) => {}
+        }
+    }
+
+    /// Moves the DOM nodes of a rendered node before `next`, or to the end of
+    /// `parent` if `next` is `None`.
+    pub(crate) fn move_before(&self, parent: &Node, next: Option<&Node>) {
+        if let VNode::VList(ref vlist) = *self {
+            for child in &vlist.childs {
+                child.move_before(parent, next);
+            }
+        } else if let VNode::VChildren(ref vchildren) = *self {
+            vchildren.move_before(parent, next);
+        } else if let VNode::VComp(ref vcomp) = *self {
+            vcomp.move_before(parent, next);
+        } else if let Some(node) = self.first_node() {
+            if next.is_some_and(|next| next.is_same_node(Some(&node))) {
+                return;
+            }
+            parent
+                .insert_before(&node, next)
+                .expect("can't move node before sibling");
+        }
+    }
 }
 
 impl<COMP: Component> VDiff for VNode<COMP> {
@@ -37,6 +129,8 @@ impl<COMP: Component> VDiff for VNode<COMP> {
                     .expect("can't remove node by VRef");
                 sibling
             }
+            VNode::VChildren(ref mut vchildren) => vchildren.detach(parent),
+            VNode::VRaw(ref mut vraw) => vraw.detach(parent),
         }
     }
 
@@ -69,6 +163,46 @@ impl<COMP: Component> VDiff for VNode<COMP> {
 
                 Some(node.to_owned())
             }
+            VNode::VChildren(ref mut vchildren) => {
+                vchildren.apply(parent, precursor, ancestor, env)
+            }
+            VNode::VRaw(ref mut vraw) => vraw.apply(parent, precursor, ancestor, env),
+        }
+    }
+
+    fn hydrate(
+        &mut self,
+        parent: &Node,
+        node: Option<Node>,
+        env: &Scope<Self::Component>,
+    ) -> Option<Node> {
+        match *self {
+            VNode::VTag(ref mut vtag) => vtag.hydrate(parent, node, env),
+            VNode::VText(ref mut vtext) => vtext.hydrate(parent, node, env),
+            VNode::VComp(ref mut vcomp) => vcomp.hydrate(parent, node, env),
+            VNode::VList(ref mut vlist) => vlist.hydrate(parent, node, env),
+            // The referenced node is already there, it replaces the existing one
+            VNode::VRef(_) => self.apply(parent, None, node.map(VNode::VRef), env),
+            VNode::VChildren(ref mut vchildren) => vchildren.hydrate(parent, node, env),
+            VNode::VRaw(ref mut vraw) => vraw.hydrate(parent, node, env),
+        }
+    }
+}
+
+impl<COMP: Component> VRender for VNode<COMP> {
+    fn render(&self, out: &mut String) {
+        match *self {
+            VNode::VTag(ref vtag) => vtag.render(out),
+            VNode::VText(ref vtext) => vtext.render(out),
+            VNode::VComp(ref vcomp) => vcomp.render(out),
+            VNode::VList(ref vlist) => vlist.render(out),
+            // A `Node` only exists in a browser, so it's safe to ask it for its markup.
+            VNode::VRef(ref node) => match node.dyn_ref::<Element>() {
+                Some(element) => out.push_str(&element.outer_html()),
+                None => escape_text(&node.text_content().unwrap_or_default(), out),
+            },
+            VNode::VChildren(ref vchildren) => vchildren.render(out),
+            VNode::VRaw(ref vraw) => vraw.render(out),
         }
     }
 }
@@ -97,6 +231,18 @@ impl<COMP: Component> From<VComp<COMP>> for VNode<COMP> {
     }
 }
 
+impl<COMP: Component> From<VRaw<COMP>> for VNode<COMP> {
+    fn from(vraw: VRaw<COMP>) -> Self {
+        VNode::VRaw(vraw)
+    }
+}
+
+impl<COMP: Component> From<Children> for VNode<COMP> {
+    fn from(children: Children) -> Self {
+        VNode::VChildren(VChildren::new(children))
+    }
+}
+
 impl<COMP: Component, T: ToString> From<T> for VNode<COMP> {
     fn from(value: T) -> Self {
         VNode::VText(VText::new(value.to_string()))
@@ -117,6 +263,8 @@ impl<COMP: Component> fmt::Debug for VNode<COMP> {
             VNode::VComp(_) => "Component<>".fmt(f),
             VNode::VList(ref vlist) => vlist.fmt(f),
             VNode::VRef(_) => "NodeReference<>".fmt(f),
+            VNode::VChildren(ref vchildren) => vchildren.fmt(f),
+            VNode::VRaw(ref vraw) => vraw.fmt(f),
         }
     }
 }
@@ -132,6 +280,10 @@ impl<COMP: Component> PartialEq for VNode<COMP> {
                 VNode::VText(ref vtext_b) => vtext_a == vtext_b,
                 _ => false,
             },
+            VNode::VRaw(ref vraw_a) => match *other {
+                VNode::VRaw(ref vraw_b) => vraw_a == vraw_b,
+                _ => false,
+            },
             _ => {
                 // TODO Implement it
                 false
@@ -0,0 +1,206 @@
+//! This module contains the implementation of a virtual node `VRaw` which
+//! renders raw HTML markup inside a container element.
+
+use super::{escape_text, hydrate_mismatch, Reform, VDiff, VNode, VRender};
+use html::{Component, Scope};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::cmp::PartialEq;
+use std::fmt;
+use std::marker::PhantomData;
+use wasm_bindgen::JsCast;
+use web_sys::{window, Element, Node};
+
+/// A function which cleans untrusted markup (ie. removes scripts and handlers).
+type Sanitizer = Box<dyn Fn(&str) -> String>;
+
+thread_local! {
+    static SANITIZER: RefCell<Option<Sanitizer>> = RefCell::new(None);
+}
+
+/// Sets the sanitizer which `VRaw::sanitized` passes markup through.
+/// Plaster doesn't clean markup itself, use a sanitizer library for it.
+pub fn set_sanitizer<F>(sanitizer: F)
+where
+    F: Fn(&str) -> String + 'static,
+{
+    SANITIZER.with(|cell| *cell.borrow_mut() = Some(Box::new(sanitizer)));
+}
+
+/// A virtual node which sets raw HTML markup as `innerHTML` of a container
+/// element (`<div>` by default).
+///
+/// The markup isn't escaped, so markup from users makes the app vulnerable
+/// to XSS. Use `VRaw::trusted` only for markup the app controls and
+/// `VRaw::sanitized` for everything else.
+pub struct VRaw<COMP: Component> {
+    /// A tag of the container element.
+    tag: Cow<'static, str>,
+    /// The markup inside the container.
+    pub html: String,
+    /// A reference to the container element.
+    pub reference: Option<Element>,
+    _comp: PhantomData<COMP>,
+}
+
+impl<COMP: Component> VRaw<COMP> {
+    /// Creates a node with markup which is trusted to be safe.
+    pub fn trusted<S: Into<String>>(html: S) -> Self {
+        VRaw {
+            tag: "div".into(),
+            html: html.into(),
+            reference: None,
+            _comp: PhantomData,
+        }
+    }
+
+    /// Creates a node with markup cleaned by the sanitizer set with `set_sanitizer`.
+    /// If there is no sanitizer the markup is escaped and rendered as text.
+    pub fn sanitized(html: &str) -> Self {
+        let html = SANITIZER.with(|cell| match *cell.borrow() {
+            Some(ref sanitizer) => sanitizer(html),
+            None => {
+                warn!("there is no sanitizer for raw HTML, it's escaped");
+                let mut escaped = String::new();
+                escape_text(html, &mut escaped);
+                escaped
+            }
+        });
+        VRaw::trusted(html)
+    }
+
+    /// Sets a tag of the container element (ie. `span` for inline markup).
+    pub fn with_tag<S: Into<Cow<'static, str>>>(mut self, tag: S) -> Self {
+        self.tag = tag.into();
+        self
+    }
+
+    /// Returns the tag of the container element.
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+}
+
+impl<COMP: Component> VDiff for VRaw<COMP> {
+    type Component = COMP;
+
+    /// Remove VRaw from parent.
+    fn detach(&mut self, parent: &Node) -> Option<Node> {
+        let node = self
+            .reference
+            .take()
+            .expect("tried to remove not rendered VRaw from DOM");
+        let sibling = node.next_sibling();
+        if parent.remove_child(&node).is_err() {
+            warn!("Node not found to remove VRaw");
+        }
+        sibling
+    }
+
+    /// Renders the markup over the existing container, but only if the markup has changed.
+    fn apply(
+        &mut self,
+        parent: &Node,
+        precursor: Option<&Node>,
+        ancestor: Option<VNode<Self::Component>>,
+        _: &Scope<Self::Component>,
+    ) -> Option<Node> {
+        assert!(
+            self.reference.is_none(),
+            "reference is ignored so must not be set"
+        );
+        let reform = match ancestor {
+            Some(VNode::VRaw(mut vraw)) => {
+                if self.tag == vraw.tag {
+                    self.reference = vraw.reference.take();
+                    if self.html != vraw.html {
+                        if let Some(ref element) = self.reference {
+                            element.set_inner_html(&self.html);
+                        }
+                    }
+                    Reform::Keep
+                } else {
+                    Reform::Before(vraw.detach(parent))
+                }
+            }
+            Some(mut vnode) => Reform::Before(vnode.detach(parent)),
+            None => Reform::Before(None),
+        };
+        match reform {
+            Reform::Keep => {}
+            Reform::Before(before) => {
+                let element = window()
+                    .expect("context needs a window")
+                    .document()
+                    .expect("window needs a document")
+                    .create_element(&self.tag)
+                    .expect("can't create element for vraw");
+                element.set_inner_html(&self.html);
+                let next = before.or_else(|| precursor.and_then(|node| node.next_sibling()));
+                parent
+                    .insert_before(&element, next.as_ref())
+                    .expect("can't insert raw markup");
+                self.reference = Some(element);
+            }
+        }
+        self.reference.as_ref().map(|e| e.to_owned().into())
+    }
+
+    fn hydrate(
+        &mut self,
+        parent: &Node,
+        node: Option<Node>,
+        env: &Scope<Self::Component>,
+    ) -> Option<Node> {
+        assert!(
+            self.reference.is_none(),
+            "reference is ignored so must not be set"
+        );
+        match node.map(|node| node.dyn_into::<Element>()) {
+            Some(Ok(element)) if element.tag_name().eq_ignore_ascii_case(&self.tag) => {
+                // Browsers normalize markup, so it's compared to get it right at least
+                if element.inner_html() != self.html {
+                    element.set_inner_html(&self.html);
+                }
+                self.reference = Some(element);
+                self.reference.as_ref().map(|e| e.to_owned().into())
+            }
+            Some(Ok(element)) => {
+                warn!("hydration mismatch: expected raw markup in <{}>", self.tag);
+                hydrate_mismatch(self, parent, Some(element.into()), env)
+            }
+            Some(Err(node)) => {
+                warn!("hydration mismatch: expected raw markup in <{}>", self.tag);
+                hydrate_mismatch(self, parent, Some(node), env)
+            }
+            None => {
+                warn!("hydration mismatch: expected raw markup in <{}>", self.tag);
+                hydrate_mismatch(self, parent, None, env)
+            }
+        }
+    }
+}
+
+impl<COMP: Component> VRender for VRaw<COMP> {
+    fn render(&self, out: &mut String) {
+        out.push('<');
+        out.push_str(&self.tag);
+        out.push('>');
+        out.push_str(&self.html);
+        out.push_str("</");
+        out.push_str(&self.tag);
+        out.push('>');
+    }
+}
+
+impl<COMP: Component> fmt::Debug for VRaw<COMP> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "VRaw {{ tag: {}, html: {} }}", self.tag, self.html)
+    }
+}
+
+impl<COMP: Component> PartialEq for VRaw<COMP> {
+    fn eq(&self, other: &VRaw<COMP>) -> bool {
+        self.tag == other.tag && self.html == other.html
+    }
+}
@@ -1,13 +1,31 @@
 //! This module contains the implementation of a virtual element node `VTag`.
 
-use super::{Attributes, Classes, Listener, Listeners, Patch, Reform, VDiff, VNode};
-use html::{Component, EventListenerHandle, Scope};
+use super::{
+    diff_children, escape_attribute, escape_text, hydrate_children, hydrate_mismatch, Attributes,
+    Classes, Listener, Listeners, Patch, Properties, Reform, Style, VDiff, VNode, VRender,
+};
+use html::{Component, EventListenerHandle, NodeRef, Scope};
+use js_sys::Reflect;
 use std::borrow::Cow;
 use std::cmp::PartialEq;
 use std::collections::HashSet;
 use std::fmt;
-use wasm_bindgen::JsCast;
-use web_sys::{window, Element, HtmlInputElement, HtmlTextAreaElement, Node};
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{
+    window, CssStyleDeclaration, Element, HtmlElement, HtmlInputElement, HtmlTextAreaElement, Node,
+    SvgElement,
+};
+
+/// The namespace of SVG elements.
+pub const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+/// The namespace of MathML elements.
+pub const MATHML_NAMESPACE: &str = "http://www.w3.org/1998/Math/MathML";
+/// The namespace of `xlink:` attributes (ie. `xlink:href` of SVG elements).
+const XLINK_NAMESPACE: &str = "http://www.w3.org/1999/xlink";
+/// The namespace of `xml:` attributes (ie. `xml:lang`).
+const XML_NAMESPACE: &str = "http://www.w3.org/XML/1998/namespace";
+/// The namespace of `xmlns` attributes.
+const XMLNS_NAMESPACE: &str = "http://www.w3.org/2000/xmlns/";
 
 /// A type for a virtual
 /// [Element](https://developer.mozilla.org/en-US/docs/Web/API/Element)
@@ -17,14 +35,24 @@ pub struct VTag<COMP: Component> {
     tag: Cow<'static, str>,
     /// A reference to the `Element`.
     pub reference: Option<Element>,
+    /// A key which identifies the element among its siblings, so it can be
+    /// reused and moved instead of re-rendered when the list of siblings changes.
+    pub key: Option<String>,
+    /// A reference which is filled in with the rendered element.
+    pub node_ref: NodeRef,
     /// List of attached listeners.
     pub listeners: Listeners<COMP>,
     /// List of attributes.
     pub attributes: Attributes,
+    /// JS properties of the element (ie. `.open=true` of a custom element).
+    /// They aren't rendered to strings, because they exist in a browser only.
+    pub properties: Properties,
     /// The list of children nodes. Which also could have own children.
     pub childs: Vec<VNode<COMP>>,
     /// List of attached classes.
     pub classes: Classes,
+    /// The inline style which is applied property by property.
+    pub style: Style,
     /// Contains a value of an
     /// [HtmlInputElement](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/input).
     pub value: Option<String>,
@@ -41,6 +69,9 @@ pub struct VTag<COMP: Component> {
     /// _Service field_. Keeps handler for attached listeners
     /// to have an opportunity to drop them later.
     captured: Vec<EventListenerHandle>,
+    /// _Service field_. The namespace the element was created in,
+    /// `None` for HTML elements.
+    namespace: Option<&'static str>,
 }
 
 impl<COMP: Component> VTag<COMP> {
@@ -49,10 +80,15 @@ impl<COMP: Component> VTag<COMP> {
         VTag {
             tag: tag.into().replace("_", "-").into(),
             reference: None,
+            key: None,
+            node_ref: NodeRef::default(),
             classes: Classes::new(),
+            style: Style::new(),
             attributes: Attributes::new(),
+            properties: Properties::new(),
             listeners: Vec::new(),
             captured: Vec::new(),
+            namespace: None,
             childs: Vec::new(),
             value: None,
             kind: None,
@@ -67,6 +103,23 @@ impl<COMP: Component> VTag<COMP> {
         &self.tag
     }
 
+    /// Returns the namespace of the rendered element (ie. `SVG_NAMESPACE`),
+    /// `None` for HTML elements and elements which are not rendered yet.
+    pub fn namespace(&self) -> Option<&str> {
+        self.namespace
+    }
+
+    /// Sets the reference which will be filled in with the rendered element.
+    pub fn set_node_ref(&mut self, node_ref: &NodeRef) {
+        self.node_ref = node_ref.clone();
+    }
+
+    /// Sets the key of the element. Siblings with keys are matched by key
+    /// instead of by position when the children of a node are diffed.
+    pub fn set_key<T: ToString>(&mut self, key: &T) {
+        self.key = Some(key.to_string());
+    }
+
     /// Add `VNode` child.
     pub fn add_child(&mut self, child: VNode<COMP>) {
         self.childs.push(child);
@@ -89,6 +142,13 @@ impl<COMP: Component> VTag<COMP> {
         self.classes = classes.split_whitespace().map(String::from).collect();
     }
 
+    /// Sets the inline style of this virtual node. Actually its properties will
+    /// be set by [CSSStyleDeclaration.setProperty](https://developer.mozilla.org/en-US/docs/Web/API/CSSStyleDeclaration/setProperty)
+    /// call later.
+    pub fn set_style<S: Into<Style>>(&mut self, style: S) {
+        self.style = style.into();
+    }
+
     /// Sets `value` for an
     /// [HtmlInputElement](https://developer.mozilla.org/en-US/docs/Web/HTML/Element/input).
     pub fn set_value<T: ToString>(&mut self, value: &T) {
@@ -116,6 +176,12 @@ impl<COMP: Component> VTag<COMP> {
         self.attributes.insert(name.to_owned(), value.to_string());
     }
 
+    /// Sets a JS property of the element, unlike attributes it could be
+    /// a boolean, a number or an object (ie. data of a Web Component).
+    pub fn set_property<T: Into<JsValue>>(&mut self, name: &str, value: T) {
+        self.properties.insert(name.to_owned(), value.into());
+    }
+
     /// Adds new listener to the node.
     /// It's boxed because we want to keep it in a single list.
     /// Lates `Listener::attach` called to attach actual listener to a DOM node.
@@ -199,6 +265,59 @@ impl<COMP: Component> VTag<COMP> {
         changes
     }
 
+    /// Similar to `diff_attributes` except for JS properties. Values are compared
+    /// with `===`, so objects are set again only if they are other objects.
+    fn diff_properties(&mut self, ancestor: &mut Option<Self>) -> Vec<Patch<String, JsValue>> {
+        let mut changes = Vec::new();
+        let ancestor = ancestor.as_mut().map(|ancestor| &mut ancestor.properties);
+        for (name, value) in &self.properties {
+            match ancestor.as_ref().and_then(|ancestor| ancestor.get(name)) {
+                Some(old) if old == value => {}
+                Some(_) => changes.push(Patch::Replace(name.to_owned(), value.clone())),
+                None => changes.push(Patch::Add(name.to_owned(), value.clone())),
+            }
+        }
+        if let Some(ancestor) = ancestor {
+            for name in ancestor.keys() {
+                if !self.properties.contains_key(name) {
+                    changes.push(Patch::Remove(name.to_owned()));
+                }
+            }
+        }
+        changes
+    }
+
+    /// Similar to `diff_attributes` except for properties of the style.
+    ///
+    /// Shorthand properties reset longhand ones (like `margin` and `margin-top`),
+    /// so after the first change the rest of properties are set again in order.
+    fn diff_style(&mut self, ancestor: &Option<Self>) -> Vec<Patch<String, String>> {
+        let mut changes = Vec::new();
+        let ancestor = ancestor.as_ref().map(|ancestor| &ancestor.style);
+        if let Some(ancestor) = ancestor {
+            for (name, _) in ancestor.iter() {
+                if self.style.get(name).is_none() {
+                    changes.push(Patch::Remove(name.to_owned()));
+                }
+            }
+        }
+        let mut changed = !changes.is_empty();
+        for (name, value) in self.style.iter() {
+            match ancestor.and_then(|ancestor| ancestor.get(name)) {
+                Some(old) if old == value && !changed => {}
+                Some(_) => {
+                    changes.push(Patch::Replace(name.to_owned(), value.to_owned()));
+                    changed = true;
+                }
+                None => {
+                    changes.push(Patch::Add(name.to_owned(), value.to_owned()));
+                    changed = true;
+                }
+            }
+        }
+        changes
+    }
+
     /// Similar to `diff_attributers` except there is only a single `kind`.
     fn diff_kind(&mut self, ancestor: &mut Option<Self>) -> Option<Patch<String, ()>> {
         match (
@@ -237,6 +356,34 @@ impl<COMP: Component> VTag<COMP> {
         }
     }
 
+    /// Attaches listeners of this node. DOM listeners of the ancestor are kept
+    /// for the same event types and only get new handlers, the rest are removed.
+    fn attach_listeners(&mut self, element: &Element, ancestor: Option<Self>, env: &Scope<COMP>) {
+        let mut handles = ancestor
+            .map(|ancestor| ancestor.captured)
+            .unwrap_or_default();
+
+        for mut listener in self.listeners.drain(..) {
+            let event_type = listener.event_type();
+            let modifiers = listener.modifiers();
+            // Options of a DOM listener can't be changed, so it's reused with the same ones
+            let reused = handles
+                .iter()
+                .position(|handle| handle.type_() == event_type && handle.modifiers() == modifiers);
+            let handle = match reused {
+                Some(index) => {
+                    let handle = handles.swap_remove(index);
+                    handle.set_handler(listener.handler(element, env.clone()));
+                    handle
+                }
+                None => listener.attach(element, env.clone()),
+            };
+            self.captured.push(handle);
+        }
+
+        // The rest of listeners are removed when their handles are dropped
+    }
+
     fn apply_diffs(&mut self, element: &Element, ancestor: &mut Option<Self>) {
         // Update parameters
         let changes = self.diff_classes(ancestor);
@@ -264,6 +411,39 @@ impl<COMP: Component> VTag<COMP> {
             }
         }
 
+        let changes = self.diff_properties(ancestor);
+        for change in changes {
+            // Removed properties get `undefined`, because they can't be deleted from elements
+            let (name, value) = match change {
+                Patch::Add(name, value) | Patch::Replace(name, value) => (name, value),
+                Patch::Remove(name) => (name, JsValue::UNDEFINED),
+            };
+            Reflect::set(element, &JsValue::from_str(&name), &value)
+                .expect("could not set property of element");
+        }
+
+        let changes = self.diff_style(ancestor);
+        if !changes.is_empty() {
+            match inline_style(element) {
+                Some(style) => {
+                    for change in changes {
+                        match change {
+                            Patch::Add(name, value) | Patch::Replace(name, value) => {
+                                set_property(&style, &name, &value);
+                            }
+                            Patch::Remove(name) => {
+                                style
+                                    .remove_property(&name)
+                                    .expect("could not remove a property of style");
+                            }
+                        }
+                    }
+                }
+                // Elements without CSSOM get the whole attribute
+                None => set_attribute(element, "style", &self.style.to_string()),
+            }
+        }
+
         // `input` element has extra parameters to control
         // I override behavior of attributes to make it more clear
         // and useful in templates. For example I interpret `checked`
@@ -307,6 +487,42 @@ impl<COMP: Component> VTag<COMP> {
             }
         }
     }
+
+    /// Removes attributes and classes of an adopted element which the tag doesn't have,
+    /// because hydration only adds and updates the ones of the tag.
+    fn remove_unknown_attributes(&self, element: &Element) {
+        let textarea = self.tag.eq_ignore_ascii_case("textarea");
+        for name in element.get_attribute_names().iter() {
+            let name = match name.as_string() {
+                Some(name) => name,
+                None => continue,
+            };
+            let known = self.attributes.contains_key(&name)
+                || match name.as_str() {
+                    "class" => !self.classes.is_empty(),
+                    "style" => !self.style.is_empty(),
+                    "type" => self.kind.is_some(),
+                    "value" => self.value.is_some() && !textarea,
+                    "checked" => self.checked,
+                    _ => false,
+                };
+            if !known {
+                warn!(
+                    "hydration mismatch: removed attribute {} of <{}>",
+                    name, self.tag
+                );
+                remove_attribute(element, &name);
+            }
+        }
+        let list = element.class_list();
+        for index in (0..list.length()).rev() {
+            if let Some(class) = list.item(index) {
+                if !self.classes.contains(&class) {
+                    list.remove_1(&class).expect("can't remove a class");
+                }
+            }
+        }
+    }
 }
 
 impl<COMP: Component> VDiff for VTag<COMP> {
@@ -318,6 +534,11 @@ impl<COMP: Component> VDiff for VTag<COMP> {
             .reference
             .take()
             .expect("tried to remove not rendered VTag from DOM");
+        // Nested components are removed with the element
+        for child in &mut self.childs {
+            child.destroy_components();
+        }
+        self.node_ref.clear_if(&node);
         let sibling = node.next_sibling();
         if parent.remove_child(&node).is_err() {
             warn!("Node not found to remove VTag");
@@ -338,12 +559,17 @@ impl<COMP: Component> VDiff for VTag<COMP> {
             self.reference.is_none(),
             "reference is ignored so must not be set"
         );
+        // `<svg>` and `<math>` start a namespace, their descendants inherit it
+        self.namespace = namespace_of(&self.tag, parent);
         let (reform, mut ancestor) = {
             match ancestor {
                 Some(VNode::VTag(mut vtag)) => {
-                    if self.tag == vtag.tag {
+                    if self.tag == vtag.tag && self.namespace == vtag.namespace {
                         // If tags are equal, preserve the reference that already exists.
                         self.reference = vtag.reference.take();
+                        if let Some(ref element) = self.reference {
+                            vtag.node_ref.clear_if(element);
+                        }
                         (Reform::Keep, Some(vtag))
                     } else {
                         // We have to create a new reference, remove ancestor.
@@ -368,12 +594,15 @@ impl<COMP: Component> VDiff for VTag<COMP> {
         match reform {
             Reform::Keep => {}
             Reform::Before(before) => {
-                let element = window()
+                let document = window()
                     .expect("context needs a window")
                     .document()
-                    .expect("window needs a document")
-                    .create_element(&self.tag)
-                    .expect("can't create element for vtag");
+                    .expect("window needs a document");
+                let element = match self.namespace {
+                    Some(namespace) => document.create_element_ns(Some(namespace), &self.tag),
+                    None => document.create_element(&self.tag),
+                }
+                .expect("can't create element for vtag");
                 if let Some(sibling) = before {
                     parent
                         .insert_before(&element, Some(&sibling))
@@ -395,63 +624,192 @@ impl<COMP: Component> VDiff for VTag<COMP> {
         }
 
         let element = self.reference.clone().expect("element expected");
+        self.node_ref.set(Some(element.clone().into()));
 
         {
-            let mut ancestor_childs = {
+            let ancestor_childs = {
                 if let Some(ref mut a) = ancestor {
-                    a.childs.drain(..).map(Some).collect::<Vec<_>>()
+                    a.childs.drain(..).collect::<Vec<_>>()
                 } else {
                     Vec::new()
                 }
             };
 
             self.apply_diffs(&element, &mut ancestor);
+            self.attach_listeners(&element, ancestor, env);
 
-            // Every render it removes all listeners and attach it back later
-            // TODO Compare references of handler to do listeners update better
-            if let Some(mut ancestor) = ancestor {
-                for handle in ancestor.captured.drain(..) {
-                    handle.remove();
+            // Start with an empty precursor, because it put childs to itself
+            diff_children(&element, None, &mut self.childs, ancestor_childs, env);
+        }
+        self.reference.as_ref().map(|e| e.to_owned().into())
+    }
+
+    fn hydrate(
+        &mut self,
+        parent: &Node,
+        node: Option<Node>,
+        env: &Scope<Self::Component>,
+    ) -> Option<Node> {
+        assert!(
+            self.reference.is_none(),
+            "reference is ignored so must not be set"
+        );
+        let element = match node.map(|node| node.dyn_into::<Element>()) {
+            Some(Ok(element)) => {
+                if !element.tag_name().eq_ignore_ascii_case(&self.tag) {
+                    warn!(
+                        "hydration mismatch: expected <{}>, found <{}>",
+                        self.tag,
+                        element.tag_name()
+                    );
+                    return hydrate_mismatch(self, parent, Some(element.into()), env);
                 }
+                element
+            }
+            Some(Err(node)) => {
+                warn!(
+                    "hydration mismatch: expected <{}>, found {}",
+                    self.tag,
+                    node.node_name()
+                );
+                return hydrate_mismatch(self, parent, Some(node), env);
+            }
+            None => {
+                warn!("hydration mismatch: expected <{}>, found nothing", self.tag);
+                return hydrate_mismatch(self, parent, None, env);
             }
+        };
+
+        self.namespace = match element.namespace_uri() {
+            Some(ref namespace) if namespace == SVG_NAMESPACE => Some(SVG_NAMESPACE),
+            Some(ref namespace) if namespace == MATHML_NAMESPACE => Some(MATHML_NAMESPACE),
+            _ => None,
+        };
+        self.remove_unknown_attributes(&element);
+        self.apply_diffs(&element, &mut None);
+        self.attach_listeners(&element, None, env);
+
+        let last = hydrate_children(&element, element.first_child(), &mut self.childs, env);
+        // Remove whatever was rendered in excess
+        let mut excess = match last {
+            Some(node) => node.next_sibling(),
+            None => element.first_child(),
+        };
+        while let Some(node) = excess {
+            excess = node.next_sibling();
+            warn!(
+                "hydration mismatch: removed {} in <{}>",
+                node.node_name(),
+                self.tag
+            );
+            element.remove_child(&node).expect("can't remove a child");
+        }
+
+        self.node_ref.set(Some(element.clone().into()));
+        self.reference = Some(element);
+        self.reference.as_ref().map(|e| e.to_owned().into())
+    }
+}
 
-            for mut listener in self.listeners.drain(..) {
-                let handle = listener.attach(&element, env.clone());
-                self.captured.push(handle);
+/// Elements which can't have children and must not have a closing tag.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Elements which contain raw text, their content must not be escaped.
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style"];
+
+impl<COMP: Component> VRender for VTag<COMP> {
+    fn render(&self, out: &mut String) {
+        out.push('<');
+        out.push_str(&self.tag);
+
+        // Sort classes and attributes to get a stable output.
+        let mut classes = self.classes.iter().map(String::as_str).collect::<Vec<_>>();
+        if !classes.is_empty() {
+            classes.sort();
+            render_attribute("class", &classes.join(" "), out);
+        }
+        let style = self.style.to_string();
+        let mut attributes = self
+            .attributes
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect::<Vec<_>>();
+        if !self.style.is_empty() && !self.attributes.contains_key("style") {
+            attributes.push(("style", &style));
+        }
+        attributes.sort();
+        for (name, value) in attributes {
+            render_attribute(name, value, out);
+        }
+        if let Some(ref kind) = self.kind {
+            if !self.attributes.contains_key("type") {
+                render_attribute("type", kind, out);
             }
+        }
 
-            let mut self_childs = self.childs.iter_mut().map(Some).collect::<Vec<_>>();
-            // Process children
-            let diff = self_childs.len() as i32 - ancestor_childs.len() as i32;
-            if diff > 0 {
-                for _ in 0..diff {
-                    ancestor_childs.push(None);
-                }
-            } else if diff < 0 {
-                for _ in 0..-diff {
-                    self_childs.push(None);
-                }
+        let textarea = self.tag.eq_ignore_ascii_case("textarea");
+        if let Some(ref value) = self.value {
+            if !textarea {
+                render_attribute("value", value, out);
             }
-            // Start with an empty precursor, because it put childs to itself
-            let mut precursor = None;
-            for pair in self_childs.into_iter().zip(ancestor_childs) {
-                match pair {
-                    (Some(left), right) => {
-                        precursor = left.apply(&element, precursor.as_ref(), right, &env);
-                    }
-                    (None, Some(mut right)) => {
-                        right.detach(&element);
-                    }
-                    (None, None) => {
-                        panic!("redundant iterations during diff");
-                    }
-                }
+        }
+        if self.checked {
+            out.push_str(" checked");
+        }
+        out.push('>');
+
+        if VOID_ELEMENTS
+            .iter()
+            .any(|tag| self.tag.eq_ignore_ascii_case(tag))
+        {
+            return;
+        }
+        if textarea {
+            if let Some(ref value) = self.value {
+                escape_text(value, out);
             }
         }
-        self.reference.as_ref().map(|e| e.to_owned().into())
+        let raw_text = RAW_TEXT_ELEMENTS
+            .iter()
+            .any(|tag| self.tag.eq_ignore_ascii_case(tag));
+        for child in &self.childs {
+            if raw_text {
+                render_raw_text(child, out);
+            } else {
+                child.render(out);
+            }
+        }
+        out.push_str("</");
+        out.push_str(&self.tag);
+        out.push('>');
     }
 }
 
+/// Writes text nodes as they are, other nodes are rendered as usual.
+fn render_raw_text<COMP: Component>(node: &VNode<COMP>, out: &mut String) {
+    match *node {
+        VNode::VText(ref vtext) => out.push_str(&vtext.text),
+        VNode::VList(ref vlist) => {
+            for child in &vlist.childs {
+                render_raw_text(child, out);
+            }
+        }
+        _ => node.render(out),
+    }
+}
+
+/// Writes an attribute with an escaped value.
+fn render_attribute(name: &str, value: &str, out: &mut String) {
+    out.push(' ');
+    out.push_str(name);
+    out.push_str("=\"");
+    escape_attribute(value, out);
+    out.push('"');
+}
+
 impl<COMP: Component> fmt::Debug for VTag<COMP> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -462,19 +820,88 @@ impl<COMP: Component> fmt::Debug for VTag<COMP> {
     }
 }
 
+/// Returns the namespace of an element with the tag rendered in the parent,
+/// `None` for HTML elements.
+fn namespace_of(tag: &str, parent: &Node) -> Option<&'static str> {
+    if tag == "svg" {
+        return Some(SVG_NAMESPACE);
+    }
+    if tag == "math" {
+        return Some(MATHML_NAMESPACE);
+    }
+    let parent = parent.dyn_ref::<Element>()?;
+    match parent.namespace_uri() {
+        // Children of `foreignObject` and `annotation-xml` are HTML again
+        Some(ref namespace) if namespace == SVG_NAMESPACE => {
+            if parent.local_name() == "foreignObject" {
+                None
+            } else {
+                Some(SVG_NAMESPACE)
+            }
+        }
+        Some(ref namespace) if namespace == MATHML_NAMESPACE => {
+            if parent.local_name() == "annotation-xml" {
+                None
+            } else {
+                Some(MATHML_NAMESPACE)
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Returns the namespace of a prefixed attribute (ie. `xlink:href`).
+fn attribute_namespace(name: &str) -> Option<&'static str> {
+    if name == "xmlns" || name.starts_with("xmlns:") {
+        Some(XMLNS_NAMESPACE)
+    } else if name.starts_with("xlink:") {
+        Some(XLINK_NAMESPACE)
+    } else if name.starts_with("xml:") {
+        Some(XML_NAMESPACE)
+    } else {
+        None
+    }
+}
+
 // todo: remove / inline these helper methods?
 /// Set attribute on an element.
 fn set_attribute(element: &Element, name: &str, value: &str) {
-    element
-        .set_attribute(name, value)
-        .expect("could not set attribute on element");
+    match attribute_namespace(name) {
+        Some(namespace) => element.set_attribute_ns(Some(namespace), name, value),
+        None => element.set_attribute(name, value),
+    }
+    .expect("could not set attribute on element");
 }
 
 /// Removes attribute from a element by name.
 fn remove_attribute(element: &Element, name: &str) {
-    element
-        .remove_attribute(name)
-        .expect("could not remove attribute on element");
+    match attribute_namespace(name) {
+        Some(namespace) => {
+            // Namespaced attributes are removed by their local names
+            let local_name = name.splitn(2, ':').last().unwrap_or(name);
+            element.remove_attribute_ns(Some(namespace), local_name)
+        }
+        None => element.remove_attribute(name),
+    }
+    .expect("could not remove attribute on element");
+}
+
+/// Returns the inline style of an HTML or an SVG element.
+fn inline_style(element: &Element) -> Option<CssStyleDeclaration> {
+    if let Some(element) = element.dyn_ref::<HtmlElement>() {
+        Some(element.style())
+    } else {
+        element.dyn_ref::<SvgElement>().map(SvgElement::style)
+    }
+}
+
+/// Sets a property of a style, the value could end with `!important`.
+fn set_property(style: &CssStyleDeclaration, name: &str, value: &str) {
+    let result = match value.find("!important") {
+        Some(index) => style.set_property_with_priority(name, value[..index].trim(), "important"),
+        None => style.set_property(name, value),
+    };
+    result.expect("could not set a property of style");
 }
 
 /// Set `checked` value for the `HtmlInputElement`.
@@ -488,6 +915,10 @@ impl<COMP: Component> PartialEq for VTag<COMP> {
             return false;
         }
 
+        if self.key != other.key {
+            return false;
+        }
+
         if self.value != other.value {
             return false;
         }
@@ -517,10 +948,18 @@ impl<COMP: Component> PartialEq for VTag<COMP> {
             return false;
         }
 
+        if self.properties != other.properties {
+            return false;
+        }
+
         if self.classes != other.classes {
             return false;
         }
 
+        if self.style != other.style {
+            return false;
+        }
+
         if self.childs.len() != other.childs.len() {
             return false;
         }
@@ -1,10 +1,11 @@
 //! This module contains the implementation of a virtual text node `VText`.
 
-use super::{Reform, VDiff, VNode};
+use super::{escape_text, hydrate_mismatch, Reform, VDiff, VNode, VRender};
 use html::{Component, Scope};
 use std::cmp::PartialEq;
 use std::fmt;
 use std::marker::PhantomData;
+use wasm_bindgen::JsCast;
 use web_sys::{window, Node, Text};
 
 /// A type for a virtual
@@ -101,6 +102,67 @@ impl<COMP: Component> VDiff for VText<COMP> {
         }
         self.reference.as_ref().map(|t| t.to_owned().into())
     }
+
+    fn hydrate(
+        &mut self,
+        parent: &Node,
+        node: Option<Node>,
+        env: &Scope<Self::Component>,
+    ) -> Option<Node> {
+        assert!(
+            self.reference.is_none(),
+            "reference is ignored so must not be set"
+        );
+        match node.map(|node| node.dyn_into::<Text>()) {
+            Some(Ok(text)) => {
+                let existing = text.node_value().unwrap_or_default();
+                if existing != self.text {
+                    if existing.starts_with(&self.text) {
+                        // Adjacent text nodes are merged by the HTML parser,
+                        // so the rest belongs to the next text node.
+                        let offset = self.text.encode_utf16().count() as u32;
+                        text.split_text(offset).expect("can't split text node");
+                    } else {
+                        warn!("hydration mismatch: expected text {:?}", self.text);
+                        text.set_node_value(Some(&self.text));
+                    }
+                }
+                self.reference = Some(text);
+                self.reference.as_ref().map(|t| t.to_owned().into())
+            }
+            Some(Err(node)) => {
+                if self.text.is_empty() {
+                    // Empty text isn't rendered to a string at all, so the existing
+                    // node belongs to the next sibling.
+                    let element = window()
+                        .expect("context needs a window")
+                        .document()
+                        .expect("window needs a document")
+                        .create_text_node("");
+                    parent
+                        .insert_before(&element, Some(&node))
+                        .expect("can't insert text before sibling");
+                    self.reference = Some(element);
+                    self.reference.as_ref().map(|t| t.to_owned().into())
+                } else {
+                    warn!("hydration mismatch: expected text {:?}", self.text);
+                    hydrate_mismatch(self, parent, Some(node), env)
+                }
+            }
+            None => {
+                if !self.text.is_empty() {
+                    warn!("hydration mismatch: expected text {:?}", self.text);
+                }
+                hydrate_mismatch(self, parent, None, env)
+            }
+        }
+    }
+}
+
+impl<COMP: Component> VRender for VText<COMP> {
+    fn render(&self, out: &mut String) {
+        escape_text(&self.text, out);
+    }
 }
 
 impl<COMP: Component> fmt::Debug for VText<COMP> {
@@ -0,0 +1,60 @@
+extern crate plaster;
+#[macro_use]
+extern crate wasm_bindgen_test;
+extern crate serde;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+use plaster::agent::Discoverer;
+use plaster::prelude::worker::*;
+use plaster::prelude::Callback;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct Ping;
+
+impl Transferable for Ping {}
+
+/// An agent which lives in a worker which never loads.
+struct Remote<REACH> {
+    _reach: ::std::marker::PhantomData<REACH>,
+}
+
+impl<REACH: Discoverer + 'static> Agent for Remote<REACH> {
+    type Reach = REACH;
+    type Message = ();
+    type Input = Ping;
+    type Output = Ping;
+
+    fn create(_: AgentLink<Self>) -> Self {
+        Remote {
+            _reach: ::std::marker::PhantomData,
+        }
+    }
+
+    fn update(&mut self, _: Self::Message) {}
+
+    fn handle(&mut self, _: Self::Input, _: HandlerId) {}
+
+    fn name_of_resource() -> &'static str {
+        "missing_worker.js"
+    }
+}
+
+#[wasm_bindgen_test]
+fn it_drops_private_bridges_before_the_worker_loads() {
+    let mut bridge = Remote::<Private>::bridge(Callback::from(|_| ()));
+    bridge.send(Ping);
+    drop(bridge);
+}
+
+#[wasm_bindgen_test]
+fn it_drops_public_bridges_before_the_worker_loads() {
+    let mut bridge = Remote::<Public>::bridge(Callback::from(|_| ()));
+    bridge.send(Ping);
+    drop(bridge);
+
+    // The worker was terminated, so a new bridge spawns a new one
+    let bridge = Remote::<Public>::bridge(Callback::from(|_| ()));
+    drop(bridge);
+}
@@ -0,0 +1,180 @@
+#[macro_use]
+extern crate plaster;
+#[macro_use]
+extern crate wasm_bindgen_test;
+extern crate js_sys;
+extern crate wasm_bindgen;
+extern crate web_sys;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+use plaster::prelude::*;
+use std::cell::RefCell;
+use wasm_bindgen::prelude::*;
+use web_sys::window;
+
+thread_local! {
+    static LOG: RefCell<Vec<&'static str>> = RefCell::new(Vec::new());
+    static FRAMES: RefCell<Vec<js_sys::Function>> = RefCell::new(Vec::new());
+    static BUMP_CHILD: RefCell<Option<Callback<()>>> = RefCell::new(None);
+}
+
+/// Returns rendered components and clears the log.
+fn take_log() -> Vec<&'static str> {
+    LOG.with(|log| log.borrow_mut().drain(..).collect())
+}
+
+/// Replaces `requestAnimationFrame` of the window, so frames run only with `run_frame`.
+fn hold_frames() {
+    let request = Closure::wrap(Box::new(|callback: js_sys::Function| {
+        FRAMES.with(|frames| frames.borrow_mut().push(callback));
+        0
+    }) as Box<dyn FnMut(js_sys::Function) -> i32>);
+    js_sys::Reflect::set(
+        &window().unwrap(),
+        &JsValue::from_str("requestAnimationFrame"),
+        request.as_ref(),
+    )
+    .unwrap();
+    request.forget();
+}
+
+/// Runs the callbacks of requested animation frames, returns how many there were.
+fn run_frame() -> usize {
+    let callbacks: Vec<_> = FRAMES.with(|frames| frames.borrow_mut().drain(..).collect());
+    for callback in &callbacks {
+        callback.call0(&JsValue::NULL).unwrap();
+    }
+    callbacks.len()
+}
+
+/// Renders the child with its count, `true` messages change the count.
+struct Parent {
+    count: usize,
+}
+
+impl Component for Parent {
+    type Message = bool;
+    type Properties = ();
+
+    fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Parent { count: 0 }
+    }
+
+    fn update(&mut self, change: Self::Message) -> ShouldRender {
+        if change {
+            self.count += 1;
+        }
+        true
+    }
+}
+
+impl Renderable<Parent> for Parent {
+    fn view(&self) -> Html<Self> {
+        LOG.with(|log| log.borrow_mut().push("parent"));
+        html! {
+            <div><Child: count=self.count, /></div>
+        }
+    }
+}
+
+#[derive(Clone, Default, PartialEq)]
+struct ChildProps {
+    count: usize,
+}
+
+struct Child {
+    count: usize,
+    bumps: usize,
+}
+
+impl Component for Child {
+    type Message = ();
+    type Properties = ChildProps;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        BUMP_CHILD.with(|bump| *bump.borrow_mut() = Some(link.send_back(|()| ())));
+        Child {
+            count: props.count,
+            bumps: 0,
+        }
+    }
+
+    fn update(&mut self, _: Self::Message) -> ShouldRender {
+        self.bumps += 1;
+        true
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.count = props.count;
+        true
+    }
+}
+
+impl Renderable<Child> for Child {
+    fn view(&self) -> Html<Self> {
+        LOG.with(|log| log.borrow_mut().push("child"));
+        html! {
+            <span>{ format!("{} {}", self.count, self.bumps) }</span>
+        }
+    }
+}
+
+fn bump_child() {
+    BUMP_CHILD.with(|bump| bump.borrow().as_ref().unwrap().emit(()));
+}
+
+/// Sends messages to the child before the parent, the parent doesn't change the child.
+#[wasm_bindgen_test]
+fn it_renders_each_component_once_per_frame_parents_first() {
+    hold_frames();
+    let element = window()
+        .unwrap()
+        .document()
+        .unwrap()
+        .create_element("div")
+        .unwrap();
+    let mut scope = App::<Parent>::new()
+        .batch_renders()
+        .mount(element.clone(), None);
+    assert_eq!(take_log(), vec!["parent", "child"]);
+
+    bump_child();
+    bump_child();
+    scope.send_message(false);
+    bump_child();
+    scope.send_message(false);
+    assert!(take_log().is_empty());
+
+    assert_eq!(run_frame(), 1);
+    assert_eq!(take_log(), vec!["parent", "child"]);
+    assert_eq!(element.inner_html(), "<div><span>0 3</span></div>");
+    assert_eq!(run_frame(), 0);
+}
+
+/// The parent changes the child, so the child is rendered by the parent and not again.
+#[wasm_bindgen_test]
+fn it_renders_children_changed_by_parents_once_per_frame() {
+    hold_frames();
+    let element = window()
+        .unwrap()
+        .document()
+        .unwrap()
+        .create_element("div")
+        .unwrap();
+    let mut scope = App::<Parent>::new()
+        .batch_renders()
+        .mount(element.clone(), None);
+    assert_eq!(take_log(), vec!["parent", "child"]);
+
+    bump_child();
+    scope.send_message(true);
+    bump_child();
+    scope.send_message(true);
+    assert!(take_log().is_empty());
+
+    assert_eq!(run_frame(), 1);
+    assert_eq!(take_log(), vec!["parent", "child"]);
+    assert_eq!(element.inner_html(), "<div><span>2 2</span></div>");
+    assert_eq!(run_frame(), 0);
+}
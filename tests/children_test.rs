@@ -0,0 +1,93 @@
+#[macro_use]
+extern crate plaster;
+#[macro_use]
+extern crate wasm_bindgen_test;
+extern crate web_sys;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+use plaster::prelude::*;
+use web_sys::window;
+
+struct Layout;
+
+impl Component for Layout {
+    type Message = ();
+    type Properties = ();
+
+    fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Layout
+    }
+
+    fn update(&mut self, _: Self::Message) -> ShouldRender {
+        unimplemented!();
+    }
+}
+
+impl Renderable<Layout> for Layout {
+    fn view(&self) -> Html<Self> {
+        html! {
+            <main>
+                <Panel:>
+                    <p>{ "first" }</p>
+                    <p>{ "second" }</p>
+                </Panel>
+                <Panel:></Panel>
+            </main>
+        }
+    }
+}
+
+struct Panel {
+    props: PanelProps,
+}
+
+#[derive(PartialEq, Clone, Default)]
+struct PanelProps {
+    children: Children,
+}
+
+impl Component for Panel {
+    type Message = ();
+    type Properties = PanelProps;
+
+    fn create(props: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Panel { props }
+    }
+
+    fn update(&mut self, _: Self::Message) -> ShouldRender {
+        unimplemented!();
+    }
+}
+
+impl Renderable<Panel> for Panel {
+    fn view(&self) -> Html<Self> {
+        html! {
+            <div class="panel",>{ self.props.children.clone() }</div>
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+fn it_mounts_children_of_components() {
+    let document = window().unwrap().document().unwrap();
+    let element = document.create_element("div").unwrap();
+
+    App::<Layout>::new().mount(element.clone(), None);
+
+    assert_eq!(
+        element.inner_html(),
+        "<main><div class=\"panel\"><p>first</p><p>second</p></div>\
+         <div class=\"panel\"></div></main>"
+    );
+}
+
+#[test]
+#[should_panic(expected = "wrong closing tag: <Panel> -> </Layout>")]
+fn it_rejects_wrong_closing_tags_of_components() {
+    let _: Html<Layout> = html! {
+        <Panel:>
+            <p>{ "first" }</p>
+        </Layout>
+    };
+}
@@ -0,0 +1,228 @@
+#[macro_use]
+extern crate plaster;
+#[macro_use]
+extern crate wasm_bindgen_test;
+extern crate web_sys;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+use plaster::prelude::*;
+use std::cell::Cell;
+use web_sys::window;
+
+thread_local! {
+    static UPDATES: Cell<usize> = Cell::new(0);
+    static DROPPED: Cell<bool> = Cell::new(false);
+    static WATCHED: Cell<usize> = Cell::new(0);
+}
+
+#[derive(Clone, PartialEq)]
+struct Theme(&'static str);
+
+enum Msg {
+    Provide(&'static str),
+    Hide,
+}
+
+/// Provides the theme to the consumer until it's hidden.
+struct Provider {
+    link: ComponentLink<Provider>,
+    show: bool,
+}
+
+impl Component for Provider {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
+        link.provide_context(Theme("dark"));
+        Provider { link, show: true }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Provide(theme) => {
+                self.link.provide_context(Theme(theme));
+                false
+            }
+            Msg::Hide => {
+                self.show = false;
+                true
+            }
+        }
+    }
+}
+
+impl Renderable<Provider> for Provider {
+    fn view(&self) -> Html<Self> {
+        if self.show {
+            html! { <div><Consumer: /></div> }
+        } else {
+            html! { <div></div> }
+        }
+    }
+}
+
+/// Sets `DROPPED` when the subscription of the consumer is dropped.
+struct Guard;
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        DROPPED.with(|dropped| dropped.set(true));
+    }
+}
+
+struct Consumer {
+    theme: Option<Theme>,
+}
+
+impl Component for Consumer {
+    type Message = Theme;
+    type Properties = ();
+
+    fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let guard = Guard;
+        let theme = link.subscribe_context(move |theme: Theme| {
+            let _ = &guard;
+            theme
+        });
+        Consumer { theme }
+    }
+
+    fn update(&mut self, theme: Self::Message) -> ShouldRender {
+        UPDATES.with(|updates| updates.set(updates.get() + 1));
+        self.theme = Some(theme);
+        true
+    }
+}
+
+impl Renderable<Consumer> for Consumer {
+    fn view(&self) -> Html<Self> {
+        let theme = self.theme.as_ref().map(|theme| theme.0);
+        html! {
+            <span>{ theme.unwrap_or("none") }</span>
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+fn it_re_renders_subscribers_when_the_context_changes() {
+    let document = window().unwrap().document().unwrap();
+    let element = document.create_element("div").unwrap();
+    let mut scope = App::<Provider>::new().mount(element.clone(), None);
+    assert_eq!(element.inner_html(), "<div><span>dark</span></div>");
+
+    scope.send_message(Msg::Provide("light"));
+    assert_eq!(element.inner_html(), "<div><span>light</span></div>");
+    assert_eq!(UPDATES.with(Cell::get), 1);
+
+    // The same value doesn't notify subscribers
+    scope.send_message(Msg::Provide("light"));
+    assert_eq!(UPDATES.with(Cell::get), 1);
+
+    assert!(!DROPPED.with(Cell::get));
+    scope.send_message(Msg::Hide);
+    assert!(DROPPED.with(Cell::get));
+
+    scope.send_message(Msg::Provide("dark"));
+    assert_eq!(UPDATES.with(Cell::get), 1);
+    assert_eq!(element.inner_html(), "<div></div>");
+}
+
+enum LateMsg {
+    Provide(&'static str),
+    Bump,
+}
+
+/// Provides the theme only when it's asked to.
+struct LateProvider {
+    link: ComponentLink<LateProvider>,
+    revision: u32,
+}
+
+impl Component for LateProvider {
+    type Message = LateMsg;
+    type Properties = ();
+
+    fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
+        LateProvider { link, revision: 0 }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            LateMsg::Provide(theme) => {
+                self.link.provide_context(Theme(theme));
+                false
+            }
+            LateMsg::Bump => {
+                self.revision += 1;
+                true
+            }
+        }
+    }
+}
+
+impl Renderable<LateProvider> for LateProvider {
+    fn view(&self) -> Html<Self> {
+        html! { <div><Watcher: revision=self.revision, /></div> }
+    }
+}
+
+#[derive(Clone, PartialEq, Default)]
+struct WatcherProps {
+    revision: u32,
+}
+
+/// Subscribes to the theme every time it gets new properties.
+struct Watcher {
+    link: ComponentLink<Watcher>,
+    theme: Option<Theme>,
+}
+
+impl Component for Watcher {
+    type Message = Theme;
+    type Properties = WatcherProps;
+
+    fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let theme = link.subscribe_context(|theme: Theme| theme);
+        Watcher { link, theme }
+    }
+
+    fn update(&mut self, theme: Self::Message) -> ShouldRender {
+        WATCHED.with(|watched| watched.set(watched.get() + 1));
+        self.theme = Some(theme);
+        true
+    }
+
+    fn change(&mut self, _: Self::Properties) -> ShouldRender {
+        self.theme = self.link.subscribe_context(|theme: Theme| theme);
+        true
+    }
+}
+
+impl Renderable<Watcher> for Watcher {
+    fn view(&self) -> Html<Self> {
+        let theme = self.theme.as_ref().map(|theme| theme.0);
+        html! {
+            <span>{ theme.unwrap_or("none") }</span>
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+fn it_notifies_subscribers_about_values_provided_later_once() {
+    let document = window().unwrap().document().unwrap();
+    let element = document.create_element("div").unwrap();
+    let mut scope = App::<LateProvider>::new().mount(element.clone(), None);
+    assert_eq!(element.inner_html(), "<div><span>none</span></div>");
+
+    scope.send_message(LateMsg::Provide("dark"));
+    assert_eq!(element.inner_html(), "<div><span>dark</span></div>");
+    assert_eq!(WATCHED.with(Cell::get), 1);
+
+    // Subscribing again replaces the subscription instead of adding one
+    scope.send_message(LateMsg::Bump);
+    scope.send_message(LateMsg::Provide("light"));
+    assert_eq!(element.inner_html(), "<div><span>light</span></div>");
+    assert_eq!(WATCHED.with(Cell::get), 2);
+}
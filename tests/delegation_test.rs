@@ -0,0 +1,107 @@
+#[macro_use]
+extern crate plaster;
+#[macro_use]
+extern crate wasm_bindgen_test;
+extern crate wasm_bindgen;
+extern crate web_sys;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+use plaster::prelude::*;
+use std::cell::RefCell;
+use wasm_bindgen::JsCast;
+use web_sys::{window, Element, Event, HtmlElement};
+
+thread_local! {
+    static LOG: RefCell<Vec<&'static str>> = RefCell::new(Vec::new());
+}
+
+/// Returns handled events and clears the log.
+fn take_log() -> Vec<&'static str> {
+    LOG.with(|log| log.borrow_mut().drain(..).collect())
+}
+
+struct Tree;
+
+impl Component for Tree {
+    type Message = &'static str;
+    type Properties = ();
+
+    fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Tree
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        LOG.with(|log| log.borrow_mut().push(msg));
+        false
+    }
+}
+
+impl Renderable<Tree> for Tree {
+    fn view(&self) -> Html<Self> {
+        html! {
+            <div onclick=|_| "outer",>
+                <p onclick=|_| "middle",>
+                    <span id="bubbling", onclick=|_| "inner",></span>
+                    <span id="stopped", onclick.stop=|_| "stopped",></span>
+                    <span id="cancelled", onclick=|event| {
+                        event.stop_propagation();
+                        "cancelled"
+                    },></span>
+                </p>
+                <input id="focused", onfocus=|_| "focus",/>
+            </div>
+        }
+    }
+}
+
+fn mount() -> Element {
+    let document = window().unwrap().document().unwrap();
+    let element = document.create_element("div").unwrap();
+    App::<Tree>::new()
+        .delegate_events()
+        .mount(element.clone(), None);
+    take_log();
+    element
+}
+
+fn click(element: &Element, selector: &str) {
+    element
+        .query_selector(selector)
+        .unwrap()
+        .unwrap()
+        .dyn_into::<HtmlElement>()
+        .unwrap()
+        .click();
+}
+
+#[wasm_bindgen_test]
+fn it_dispatches_from_target_to_root() {
+    let element = mount();
+
+    click(&element, "#bubbling");
+
+    assert_eq!(take_log(), vec!["inner", "middle", "outer"]);
+}
+
+#[wasm_bindgen_test]
+fn it_stops_dispatch_when_propagation_is_stopped() {
+    let element = mount();
+
+    click(&element, "#stopped");
+    assert_eq!(take_log(), vec!["stopped"]);
+
+    click(&element, "#cancelled");
+    assert_eq!(take_log(), vec!["cancelled"]);
+}
+
+#[wasm_bindgen_test]
+fn it_listens_to_non_bubbling_events_on_elements() {
+    let element = mount();
+
+    // `focus` doesn't bubble to the mount element, so a delegated handler would miss it
+    let input = element.query_selector("#focused").unwrap().unwrap();
+    input.dispatch_event(&Event::new("focus").unwrap()).unwrap();
+
+    assert_eq!(take_log(), vec!["focus"]);
+}
@@ -0,0 +1,153 @@
+#[macro_use]
+extern crate plaster;
+#[macro_use]
+extern crate wasm_bindgen_test;
+extern crate web_sys;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+use plaster::prelude::*;
+use web_sys::{window, Element};
+
+struct Comp;
+
+impl Component for Comp {
+    type Message = ();
+    type Properties = ();
+
+    fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Comp
+    }
+
+    fn update(&mut self, _: Self::Message) -> ShouldRender {
+        unimplemented!();
+    }
+}
+
+impl Renderable<Comp> for Comp {
+    fn view(&self) -> Html<Self> {
+        html! {
+            <div class="app",>
+                <p>{ "hello" }</p>
+                <p>{ "world" }</p>
+            </div>
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+fn it_adopts_rendered_nodes() {
+    let document = window().unwrap().document().unwrap();
+    let element = document.create_element("div").unwrap();
+    element.set_inner_html(&App::<Comp>::new().render_to_string(None));
+    let rendered = element.first_element_child().unwrap();
+
+    App::<Comp>::new().hydrate(element.clone(), None);
+
+    assert_eq!(element.child_element_count(), 1);
+    assert!(rendered.is_same_node(element.first_child().as_ref()));
+    assert_eq!(
+        element.inner_html(),
+        "<div class=\"app\"><p>hello</p><p>world</p></div>"
+    );
+}
+
+/// Returns an element with the markup rendered on a server.
+fn rendered(markup: &str) -> Element {
+    let document = window().unwrap().document().unwrap();
+    let element = document.create_element("div").unwrap();
+    element.set_inner_html(markup);
+    element
+}
+
+#[wasm_bindgen_test]
+fn it_replaces_nodes_with_wrong_tags() {
+    let element = rendered("<div class=\"app\"><span>hello</span><p>world</p></div>");
+    let app = element.first_element_child().unwrap();
+    let world = app.last_element_child().unwrap();
+
+    App::<Comp>::new().hydrate(element.clone(), None);
+
+    assert_eq!(
+        element.inner_html(),
+        "<div class=\"app\"><p>hello</p><p>world</p></div>"
+    );
+    assert!(app.is_same_node(element.first_child().as_ref()));
+    assert!(world.is_same_node(app.last_child().as_ref()));
+}
+
+#[wasm_bindgen_test]
+fn it_renders_missing_nodes() {
+    let element = rendered("<div class=\"app\"><p>hello</p></div>");
+    let app = element.first_element_child().unwrap();
+    let hello = app.first_element_child().unwrap();
+
+    App::<Comp>::new().hydrate(element.clone(), None);
+
+    assert_eq!(
+        element.inner_html(),
+        "<div class=\"app\"><p>hello</p><p>world</p></div>"
+    );
+    assert!(app.is_same_node(element.first_child().as_ref()));
+    assert!(hello.is_same_node(app.first_child().as_ref()));
+}
+
+#[wasm_bindgen_test]
+fn it_removes_extra_nodes() {
+    let element = rendered("<div class=\"app\"><p>hello</p><p>world</p><p>extra</p>text</div>");
+    let app = element.first_element_child().unwrap();
+    let hello = app.first_element_child().unwrap();
+    let world = hello.next_element_sibling().unwrap();
+
+    App::<Comp>::new().hydrate(element.clone(), None);
+
+    assert_eq!(
+        element.inner_html(),
+        "<div class=\"app\"><p>hello</p><p>world</p></div>"
+    );
+    assert!(app.is_same_node(element.first_child().as_ref()));
+    assert!(hello.is_same_node(app.first_child().as_ref()));
+    assert!(world.is_same_node(app.last_child().as_ref()));
+}
+
+#[wasm_bindgen_test]
+fn it_replaces_a_leading_text_node_of_the_root() {
+    let element = rendered("\n  <div class=\"app\"><p>hello</p><p>world</p></div>");
+
+    App::<Comp>::new().hydrate(element.clone(), None);
+
+    assert_eq!(element.child_element_count(), 1);
+    assert_eq!(
+        element.inner_html(),
+        "<div class=\"app\"><p>hello</p><p>world</p></div>"
+    );
+}
+
+#[wasm_bindgen_test]
+fn it_removes_extra_root_nodes() {
+    let element = rendered("<div class=\"app\"><p>hello</p><p>world</p></div>text<p>extra</p>");
+    let app = element.first_child().unwrap();
+
+    App::<Comp>::new().hydrate(element.clone(), None);
+
+    assert!(app.is_same_node(element.first_child().as_ref()));
+    assert_eq!(
+        element.inner_html(),
+        "<div class=\"app\"><p>hello</p><p>world</p></div>"
+    );
+}
+
+#[wasm_bindgen_test]
+fn it_removes_unknown_attributes_of_adopted_nodes() {
+    let element =
+        rendered("<div class=\"app old\" id=\"stale\"><p hidden=\"\">hello</p><p>world</p></div>");
+    let app = element.first_child().unwrap();
+
+    App::<Comp>::new().hydrate(element.clone(), None);
+
+    assert!(app.is_same_node(element.first_child().as_ref()));
+    assert_eq!(
+        element.inner_html(),
+        "<div class=\"app\"><p>hello</p><p>world</p></div>"
+    );
+}
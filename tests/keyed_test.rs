@@ -0,0 +1,212 @@
+#[macro_use]
+extern crate plaster;
+#[macro_use]
+extern crate wasm_bindgen_test;
+extern crate web_sys;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+use plaster::prelude::*;
+use plaster::virtual_dom::VList;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use web_sys::{window, Element, Node};
+
+static CREATED: AtomicUsize = AtomicUsize::new(0);
+
+struct List {
+    items: Vec<u32>,
+}
+
+impl Component for List {
+    type Message = Vec<u32>;
+    type Properties = ();
+
+    fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
+        List {
+            items: vec![1, 2, 3],
+        }
+    }
+
+    fn update(&mut self, items: Self::Message) -> ShouldRender {
+        self.items = items;
+        true
+    }
+}
+
+impl Renderable<List> for List {
+    fn view(&self) -> Html<Self> {
+        let tags = self
+            .items
+            .iter()
+            .map(|item| html! { <li key=item,>{ item }</li> })
+            .collect::<VList<Self>>();
+        let comps = self
+            .items
+            .iter()
+            .map(|item| html! { <Item: key=item, id=*item, /> })
+            .collect::<VList<Self>>();
+        let pairs = self
+            .items
+            .iter()
+            .map(|item| html! { <Pair: key=item, id=*item, /> })
+            .collect::<VList<Self>>();
+        html! {
+            <div>
+                <ul>{ tags }</ul>
+                <div>{ comps }</div>
+                <div>{ pairs }</div>
+            </div>
+        }
+    }
+}
+
+/// A component which remembers the order it was created in.
+struct Item {
+    id: u32,
+    created: usize,
+}
+
+#[derive(PartialEq, Clone, Default)]
+struct ItemProps {
+    id: u32,
+}
+
+impl Component for Item {
+    type Message = ();
+    type Properties = ItemProps;
+
+    fn create(props: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Item {
+            id: props.id,
+            created: CREATED.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+
+    fn update(&mut self, _: Self::Message) -> ShouldRender {
+        unimplemented!();
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.id = props.id;
+        true
+    }
+}
+
+impl Renderable<Item> for Item {
+    fn view(&self) -> Html<Self> {
+        html! {
+            <span>{ format!("{}:{}", self.id, self.created) }</span>
+        }
+    }
+}
+
+/// A component which renders a fragment of two nodes.
+struct Pair {
+    id: u32,
+}
+
+impl Component for Pair {
+    type Message = ();
+    type Properties = ItemProps;
+
+    fn create(props: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Pair { id: props.id }
+    }
+
+    fn update(&mut self, _: Self::Message) -> ShouldRender {
+        unimplemented!();
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.id = props.id;
+        true
+    }
+}
+
+impl Renderable<Pair> for Pair {
+    fn view(&self) -> Html<Self> {
+        html! {
+            <>
+                <b>{ self.id }</b>
+                <i>{ self.id }</i>
+            </>
+        }
+    }
+}
+
+fn children(element: &Element) -> Vec<Node> {
+    let mut nodes = Vec::new();
+    let mut node = element.first_child();
+    while let Some(next) = node {
+        node = next.next_sibling();
+        nodes.push(next);
+    }
+    nodes
+}
+
+fn texts(element: &Element) -> Vec<String> {
+    children(element)
+        .iter()
+        .map(|node| node.text_content().unwrap())
+        .collect()
+}
+
+#[wasm_bindgen_test]
+fn it_reuses_keyed_children() {
+    let document = window().unwrap().document().unwrap();
+    let element = document.create_element("div").unwrap();
+
+    let mut scope = App::<List>::new().mount(element.clone(), None);
+
+    let root = element.first_element_child().unwrap();
+    let list = root.first_element_child().unwrap();
+    let comps = list.next_element_sibling().unwrap();
+    let before = children(&list);
+    let spans = texts(&comps);
+
+    scope.send_message(vec![3, 4, 2, 1]);
+
+    let after = children(&list);
+    assert_eq!(
+        list.inner_html(),
+        "<li>3</li><li>4</li><li>2</li><li>1</li>"
+    );
+    assert!(after[0].is_same_node(Some(&before[2])));
+    assert!(after[2].is_same_node(Some(&before[1])));
+    assert!(after[3].is_same_node(Some(&before[0])));
+    assert!(!before.iter().any(|node| node.is_same_node(Some(&after[1]))));
+
+    // Keyed components are moved with their state instead of being re-created
+    let moved = texts(&comps);
+    assert_eq!(moved[0], spans[2]);
+    assert_eq!(moved[2], spans[1]);
+    assert_eq!(moved[3], spans[0]);
+    assert!(moved[1].starts_with("4:"));
+    assert!(!spans.contains(&moved[1]));
+}
+
+#[wasm_bindgen_test]
+fn it_moves_all_nodes_of_keyed_components() {
+    let document = window().unwrap().document().unwrap();
+    let element = document.create_element("div").unwrap();
+
+    let mut scope = App::<List>::new().mount(element.clone(), None);
+
+    let root = element.first_element_child().unwrap();
+    let pairs = root.last_element_child().unwrap();
+    let before = children(&pairs);
+
+    scope.send_message(vec![3, 1, 2]);
+
+    let after = children(&pairs);
+    assert_eq!(
+        pairs.inner_html(),
+        "<b>3</b><i>3</i><b>1</b><i>1</i><b>2</b><i>2</i>"
+    );
+    // Both nodes of every component are moved instead of re-rendered
+    for (index, &item) in [3usize, 1, 2].iter().enumerate() {
+        let previous = (item - 1) * 2;
+        assert!(after[index * 2].is_same_node(Some(&before[previous])));
+        assert!(after[index * 2 + 1].is_same_node(Some(&before[previous + 1])));
+    }
+}
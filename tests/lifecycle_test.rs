@@ -0,0 +1,228 @@
+#[macro_use]
+extern crate plaster;
+#[macro_use]
+extern crate wasm_bindgen_test;
+extern crate web_sys;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+use plaster::prelude::*;
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+use web_sys::window;
+
+thread_local! {
+    static LOG: RefCell<Vec<&'static str>> = RefCell::new(Vec::new());
+    static WAKERS: RefCell<Vec<Waker>> = RefCell::new(Vec::new());
+    static RESOLVED: Cell<bool> = Cell::new(false);
+    static DROPPED: Cell<usize> = Cell::new(0);
+    static HOOKS: RefCell<Vec<String>> = RefCell::new(Vec::new());
+}
+
+fn log(entry: &'static str) {
+    LOG.with(|log| log.borrow_mut().push(entry));
+}
+
+/// Returns called hooks and clears the log.
+fn take_hooks() -> Vec<String> {
+    HOOKS.with(|hooks| hooks.borrow_mut().drain(..).collect())
+}
+
+fn hook(entry: String) {
+    HOOKS.with(|hooks| hooks.borrow_mut().push(entry));
+}
+
+/// A future which is pending until `RESOLVED` is set.
+struct Gate<T>(T);
+
+impl<T: Clone + Unpin> Future for Gate<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        if RESOLVED.with(Cell::get) {
+            Poll::Ready(self.0.clone())
+        } else {
+            WAKERS.with(|wakers| wakers.borrow_mut().push(cx.waker().clone()));
+            Poll::Pending
+        }
+    }
+}
+
+impl<T> Drop for Gate<T> {
+    fn drop(&mut self) {
+        DROPPED.with(|dropped| dropped.set(dropped.get() + 1));
+    }
+}
+
+/// Renders the child until it gets `false`.
+struct Parent {
+    show: bool,
+}
+
+impl Component for Parent {
+    type Message = bool;
+    type Properties = ();
+
+    fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Parent { show: true }
+    }
+
+    fn update(&mut self, show: Self::Message) -> ShouldRender {
+        self.show = show;
+        true
+    }
+}
+
+impl Renderable<Parent> for Parent {
+    fn view(&self) -> Html<Self> {
+        if self.show {
+            html! { <div><Child: /></div> }
+        } else {
+            html! { <div></div> }
+        }
+    }
+}
+
+struct Child;
+
+impl Component for Child {
+    type Message = &'static str;
+    type Properties = ();
+
+    fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
+        link.send_async(Gate("resolved"));
+        link.send_async_result(Gate(Ok("resolved")), |_: ()| "failed");
+        Child
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        log(msg);
+        false
+    }
+}
+
+impl Renderable<Child> for Child {
+    fn view(&self) -> Html<Self> {
+        html! { <span></span> }
+    }
+}
+
+#[wasm_bindgen_test]
+fn it_drops_pending_futures_of_destroyed_components() {
+    let document = window().unwrap().document().unwrap();
+    let element = document.create_element("div").unwrap();
+    let mut scope = App::<Parent>::new().mount(element, None);
+    assert_eq!(WAKERS.with(|wakers| wakers.borrow().len()), 2);
+    assert_eq!(DROPPED.with(Cell::get), 0);
+
+    scope.send_message(false);
+    assert_eq!(DROPPED.with(Cell::get), 2);
+
+    // The futures are gone, so resolving them can't reach the component
+    RESOLVED.with(|resolved| resolved.set(true));
+    for waker in WAKERS.with(|wakers| wakers.borrow_mut().drain(..).collect::<Vec<_>>()) {
+        waker.wake();
+    }
+    assert!(LOG.with(|log| log.borrow().is_empty()));
+}
+
+/// Records its hooks and renders the inner component until it gets `false`.
+struct Outer {
+    show: bool,
+}
+
+impl Component for Outer {
+    type Message = bool;
+    type Properties = ();
+
+    fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Outer { show: true }
+    }
+
+    fn update(&mut self, show: Self::Message) -> ShouldRender {
+        self.show = show;
+        true
+    }
+
+    fn rendered(&mut self, first_render: bool) {
+        hook(format!("outer rendered {}", first_render));
+    }
+
+    fn destroy(&mut self) {
+        hook("outer destroyed".to_string());
+    }
+}
+
+impl Renderable<Outer> for Outer {
+    fn view(&self) -> Html<Self> {
+        if self.show {
+            html! { <div><Inner: /></div> }
+        } else {
+            html! { <div></div> }
+        }
+    }
+}
+
+struct Inner;
+
+impl Component for Inner {
+    type Message = ();
+    type Properties = ();
+
+    fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Inner
+    }
+
+    fn update(&mut self, _: Self::Message) -> ShouldRender {
+        unimplemented!();
+    }
+
+    fn rendered(&mut self, first_render: bool) {
+        hook(format!("inner rendered {}", first_render));
+    }
+
+    fn destroy(&mut self) {
+        hook("inner destroyed".to_string());
+    }
+}
+
+impl Renderable<Inner> for Inner {
+    fn view(&self) -> Html<Self> {
+        html! { <span></span> }
+    }
+}
+
+#[wasm_bindgen_test]
+fn it_notifies_children_about_rendering_before_parents() {
+    let document = window().unwrap().document().unwrap();
+    let element = document.create_element("div").unwrap();
+    take_hooks();
+
+    let mut scope = App::<Outer>::new().mount(element, None);
+    assert_eq!(
+        take_hooks(),
+        vec!["inner rendered true", "outer rendered true"]
+    );
+
+    scope.send_message(false);
+    assert_eq!(
+        take_hooks(),
+        vec!["inner destroyed", "outer rendered false"]
+    );
+}
+
+#[wasm_bindgen_test]
+fn it_notifies_hydrated_children_about_rendering_before_parents() {
+    let document = window().unwrap().document().unwrap();
+    let element = document.create_element("div").unwrap();
+    element.set_inner_html("<div><span></span></div>");
+    take_hooks();
+
+    App::<Outer>::new().hydrate(element, None);
+    assert_eq!(
+        take_hooks(),
+        vec!["inner rendered true", "outer rendered true"]
+    );
+}
@@ -0,0 +1,65 @@
+#[macro_use]
+extern crate plaster;
+#[macro_use]
+extern crate wasm_bindgen_test;
+extern crate web_sys;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+use plaster::prelude::*;
+use plaster::virtual_dom::vtag::SVG_NAMESPACE;
+use web_sys::window;
+
+struct Icon;
+
+impl Component for Icon {
+    type Message = ();
+    type Properties = ();
+
+    fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Icon
+    }
+
+    fn update(&mut self, _: Self::Message) -> ShouldRender {
+        unimplemented!();
+    }
+}
+
+impl Renderable<Icon> for Icon {
+    fn view(&self) -> Html<Self> {
+        html! {
+            <svg viewBox="0 0 10 10",>
+                <use xlink:href="#dot", />
+                <foreignObject>
+                    <p>{ "label" }</p>
+                </foreignObject>
+            </svg>
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+fn it_creates_elements_in_namespaces() {
+    let document = window().unwrap().document().unwrap();
+    let element = document.create_element("div").unwrap();
+
+    App::<Icon>::new().mount(element.clone(), None);
+
+    let svg = element.first_element_child().unwrap();
+    assert_eq!(svg.namespace_uri(), Some(SVG_NAMESPACE.to_string()));
+    let link = svg.first_element_child().unwrap();
+    assert_eq!(link.namespace_uri(), Some(SVG_NAMESPACE.to_string()));
+    assert_eq!(
+        link.get_attribute_ns(Some("http://www.w3.org/1999/xlink"), "href"),
+        Some("#dot".to_string())
+    );
+    let paragraph = link
+        .next_element_sibling()
+        .unwrap()
+        .first_element_child()
+        .unwrap();
+    assert_eq!(
+        paragraph.namespace_uri(),
+        Some("http://www.w3.org/1999/xhtml".to_string())
+    );
+}
@@ -0,0 +1,284 @@
+#[macro_use]
+extern crate plaster;
+
+use plaster::prelude::*;
+use plaster::virtual_dom::{VNode, VRender};
+
+struct Comp;
+
+impl Component for Comp {
+    type Message = ();
+    type Properties = ();
+
+    fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Comp
+    }
+
+    fn update(&mut self, _: Self::Message) -> ShouldRender {
+        unimplemented!();
+    }
+}
+
+impl Renderable<Comp> for Comp {
+    fn view(&self) -> Html<Self> {
+        html! {
+            <ul>
+                <Item: label="first", />
+                <Item: label="<second>", />
+            </ul>
+        }
+    }
+}
+
+struct Item {
+    label: String,
+}
+
+#[derive(PartialEq, Clone, Default)]
+struct ItemProps {
+    label: String,
+}
+
+impl Component for Item {
+    type Message = ();
+    type Properties = ItemProps;
+
+    fn create(props: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Item { label: props.label }
+    }
+
+    fn update(&mut self, _: Self::Message) -> ShouldRender {
+        unimplemented!();
+    }
+}
+
+impl Renderable<Item> for Item {
+    fn view(&self) -> Html<Self> {
+        html! {
+            <li>{ &self.label }</li>
+        }
+    }
+}
+
+#[test]
+fn it_renders_tags() {
+    let a: VNode<Comp> = html! {
+        <div id="main", class="b a",>
+            <input type="text", value="x", checked=true, />
+            <p>{ "text" }</p>
+        </div>
+    };
+
+    assert_eq!(
+        a.render_to_string(),
+        "<div class=\"a b\" id=\"main\"><input type=\"text\" value=\"x\" checked><p>text</p></div>"
+    );
+}
+
+#[test]
+fn it_escapes_text_and_attributes() {
+    let a: VNode<Comp> = html! {
+        <a title="\"quoted\" & <tag>",>{ "<script>alert(1)</script>" }</a>
+    };
+
+    assert_eq!(
+        a.render_to_string(),
+        "<a title=\"&quot;quoted&quot; &amp; &lt;tag&gt;\">&lt;script&gt;alert(1)&lt;/script&gt;</a>"
+    );
+}
+
+#[test]
+fn it_renders_text_of_scripts_and_styles_unescaped() {
+    let div: VNode<Comp> = html! {
+        <div>
+            <script>{ "if (a < b && c > d) { go(\"<b>\"); }" }</script>
+            <style>{ "a > b { content: \"&\"; }" }</style>
+        </div>
+    };
+
+    assert_eq!(
+        div.render_to_string(),
+        "<div><script>if (a < b && c > d) { go(\"<b>\"); }</script>\
+         <style>a > b { content: \"&\"; }</style></div>"
+    );
+}
+
+#[test]
+fn it_renders_styles() {
+    let a: VNode<Comp> = html! {
+        <div style=Style::new().with("color", "red").with("--gap", 4),
+             title="x",>
+            <p style="margin: 0; background: url(\"a;b.png\")",></p>
+        </div>
+    };
+
+    assert_eq!(
+        a.render_to_string(),
+        "<div style=\"color: red; --gap: 4;\" title=\"x\">\
+         <p style=\"margin: 0; background: url(&quot;a;b.png&quot;);\"></p></div>"
+    );
+}
+
+#[test]
+fn it_renders_namespaced_attributes() {
+    let a: VNode<Comp> = html! {
+        <svg><use xlink:href="#icon", xml:lang="en", /></svg>
+    };
+
+    assert_eq!(
+        a.render_to_string(),
+        "<svg><use xlink:href=\"#icon\" xml:lang=\"en\"></use></svg>"
+    );
+}
+
+#[test]
+fn it_renders_child_components() {
+    let html = App::<Comp>::new().render_to_string(None);
+
+    assert_eq!(html, "<ul><li>first</li><li>&lt;second&gt;</li></ul>");
+}
+
+#[derive(Clone, PartialEq)]
+struct Theme(String);
+
+struct Provider;
+
+impl Component for Provider {
+    type Message = ();
+    type Properties = ();
+
+    fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
+        link.provide_context(Theme("dark".to_owned()));
+        Provider
+    }
+
+    fn update(&mut self, _: Self::Message) -> ShouldRender {
+        unimplemented!();
+    }
+}
+
+impl Renderable<Provider> for Provider {
+    fn view(&self) -> Html<Self> {
+        html! {
+            <div>
+                <Consumer: />
+            </div>
+        }
+    }
+}
+
+struct Consumer {
+    theme: Option<Theme>,
+}
+
+impl Component for Consumer {
+    type Message = ();
+    type Properties = ();
+
+    fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Consumer {
+            theme: link.context(),
+        }
+    }
+
+    fn update(&mut self, _: Self::Message) -> ShouldRender {
+        unimplemented!();
+    }
+}
+
+impl Renderable<Consumer> for Consumer {
+    fn view(&self) -> Html<Self> {
+        let theme = self.theme.as_ref().map(|theme| theme.0.as_str());
+        html! {
+            <span>{ theme.unwrap_or("none") }</span>
+        }
+    }
+}
+
+#[test]
+fn it_passes_context_to_descendants() {
+    let html = App::<Provider>::new().render_to_string(None);
+
+    assert_eq!(html, "<div><span>dark</span></div>");
+}
+
+#[test]
+fn it_renders_without_context() {
+    let html = App::<Consumer>::new().render_to_string(None);
+
+    assert_eq!(html, "<span>none</span>");
+}
+
+struct Layout;
+
+impl Component for Layout {
+    type Message = ();
+    type Properties = ();
+
+    fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Layout
+    }
+
+    fn update(&mut self, _: Self::Message) -> ShouldRender {
+        unimplemented!();
+    }
+}
+
+impl Renderable<Layout> for Layout {
+    fn view(&self) -> Html<Self> {
+        html! {
+            <main>
+                <Card: title="first",>
+                    <p>{ "text" }</p>
+                    <Item: label="item", />
+                </Card>
+                <Card: title="second",></Card>
+            </main>
+        }
+    }
+}
+
+struct Card {
+    props: CardProps,
+}
+
+#[derive(PartialEq, Clone, Default)]
+struct CardProps {
+    title: String,
+    children: Children,
+}
+
+impl Component for Card {
+    type Message = ();
+    type Properties = CardProps;
+
+    fn create(props: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Card { props }
+    }
+
+    fn update(&mut self, _: Self::Message) -> ShouldRender {
+        unimplemented!();
+    }
+}
+
+impl Renderable<Card> for Card {
+    fn view(&self) -> Html<Self> {
+        html! {
+            <section>
+                <h1>{ &self.props.title }</h1>
+                { self.props.children.clone() }
+            </section>
+        }
+    }
+}
+
+#[test]
+fn it_renders_children_of_components() {
+    let html = App::<Layout>::new().render_to_string(None);
+
+    assert_eq!(
+        html,
+        "<main><section><h1>first</h1><p>text</p><li>item</li></section>\
+         <section><h1>second</h1></section></main>"
+    );
+}
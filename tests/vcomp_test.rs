@@ -71,3 +71,12 @@ fn set_properties_to_component() {
         <Comp: with props, field_2=2, />
     };
 }
+
+#[wasm_bindgen_test]
+fn set_key_to_component() {
+    let a: VNode<Comp> = html! {
+        <Comp: key="first", field_1=1, />
+    };
+
+    assert_eq!(a.key(), Some("first"));
+}
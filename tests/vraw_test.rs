@@ -0,0 +1,104 @@
+#[macro_use]
+extern crate plaster;
+#[macro_use]
+extern crate wasm_bindgen_test;
+extern crate web_sys;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+use plaster::prelude::*;
+use plaster::virtual_dom::vraw::set_sanitizer;
+use plaster::virtual_dom::{VNode, VRaw, VRender};
+use web_sys::window;
+
+struct Comp;
+
+impl Component for Comp {
+    type Message = ();
+    type Properties = ();
+
+    fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Comp
+    }
+
+    fn update(&mut self, _: Self::Message) -> ShouldRender {
+        unimplemented!();
+    }
+}
+
+impl Renderable<Comp> for Comp {
+    fn view(&self) -> Html<Self> {
+        unimplemented!();
+    }
+}
+
+#[test]
+fn it_renders_trusted_markup() {
+    let a: VNode<Comp> = html! {
+        <article>{ VRaw::trusted("<p>A <b>bold</b> move</p>").with_tag("section") }</article>
+    };
+
+    assert_eq!(
+        a.render_to_string(),
+        "<article><section><p>A <b>bold</b> move</p></section></article>"
+    );
+}
+
+#[test]
+fn it_compares_markup() {
+    let a: VNode<Comp> = VRaw::trusted("<b>a</b>").into();
+    let b: VNode<Comp> = VRaw::trusted("<b>a</b>").into();
+    let c: VNode<Comp> = VRaw::trusted("<b>a</b>").with_tag("span").into();
+
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+}
+
+#[test]
+fn it_escapes_markup_without_sanitizer() {
+    let a: VRaw<Comp> = VRaw::sanitized("<img src=x onerror=alert(1)>");
+
+    assert_eq!(
+        a.render_to_string(),
+        "<div>&lt;img src=x onerror=alert(1)&gt;</div>"
+    );
+}
+
+#[test]
+fn it_sanitizes_markup() {
+    set_sanitizer(|html| html.replace(" onerror=alert(1)", ""));
+    let a: VRaw<Comp> = VRaw::sanitized("<img src=x onerror=alert(1)>");
+
+    assert_eq!(a.render_to_string(), "<div><img src=x></div>");
+}
+
+#[wasm_bindgen_test]
+fn it_sets_inner_html() {
+    let document = window().unwrap().document().unwrap();
+    let element = document.create_element("div").unwrap();
+
+    App::<Raw>::new().mount(element.clone(), None);
+
+    assert_eq!(element.inner_html(), "<div><i>raw</i></div>");
+}
+
+struct Raw;
+
+impl Component for Raw {
+    type Message = ();
+    type Properties = ();
+
+    fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Raw
+    }
+
+    fn update(&mut self, _: Self::Message) -> ShouldRender {
+        unimplemented!();
+    }
+}
+
+impl Renderable<Raw> for Raw {
+    fn view(&self) -> Html<Self> {
+        VRaw::trusted("<i>raw</i>").into()
+    }
+}
@@ -5,8 +5,9 @@ extern crate wasm_bindgen_test;
 
 wasm_bindgen_test_configure!(run_in_browser);
 
+use plaster::html::Modifiers;
 use plaster::prelude::*;
-use plaster::virtual_dom::VNode;
+use plaster::virtual_dom::{VNode, VRender};
 
 struct Comp;
 
@@ -226,6 +227,77 @@ fn it_compares_checked() {
     assert_ne!(a, c);
 }
 
+#[test]
+fn it_compares_keys() {
+    let a: VNode<Comp> = html! {
+        <div key="a",></div>
+    };
+
+    let b: VNode<Comp> = html! {
+        <div key="a",></div>
+    };
+
+    let c: VNode<Comp> = html! {
+        <div key=1,></div>
+    };
+
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+    assert_eq!(c.key(), Some("1"));
+}
+
+#[test]
+fn it_compares_styles() {
+    let a: VNode<Comp> = html! {
+        <div style="color: red; margin: 0;",></div>
+    };
+
+    let b: VNode<Comp> = html! {
+        <div style=vec![("color", "red"), ("margin", "0")],></div>
+    };
+
+    let c: VNode<Comp> = html! {
+        <div style=Style::new().with("margin", 0).with("color", "red"),></div>
+    };
+
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+}
+
+#[test]
+fn it_sets_properties() {
+    let a: VNode<Comp> = html! {
+        <ion_toggle .checked=true, label="Wi-Fi",></ion_toggle>
+    };
+
+    if let VNode::VTag(vtag) = a {
+        assert!(vtag.properties.contains_key("checked"));
+        assert!(!vtag.attributes.contains_key("checked"));
+        assert!(vtag.attributes.contains_key("label"));
+        assert_eq!(
+            vtag.render_to_string(),
+            "<ion-toggle label=\"Wi-Fi\"></ion-toggle>"
+        );
+    } else {
+        panic!("vtag expected");
+    }
+}
+
+#[test]
+fn it_sets_node_ref() {
+    let node_ref = NodeRef::default();
+    let a: VNode<Comp> = html! {
+        <input ref=node_ref, />
+    };
+
+    if let VNode::VTag(vtag) = a {
+        assert_eq!(vtag.node_ref, node_ref);
+        assert!(node_ref.get().is_none());
+    } else {
+        panic!("vtag expected");
+    }
+}
+
 #[test]
 fn it_allows_aria_attributes() {
     let a: VNode<Comp> = html! {
@@ -259,3 +331,72 @@ fn it_allows_aria_attributes() {
         panic!("vtag expected");
     }
 }
+
+#[test]
+fn it_listens_to_standard_events() {
+    let a: VNode<Comp> = html! {
+        <video ondoubleclick=|_| (),
+               ontouchstart=|_| (),
+               onwheel=|_| (),
+               onanimationend=|_| (),
+               ontransitionend=|_| (),
+               onpaste=|_| (),
+               oncompositionend=|_| (),
+               onfocusin=|_| (),
+               onbeforeinput=|_| (),
+               onloadeddata=|_| (),
+               onerror=|_| (),>
+        </video>
+    };
+    if let VNode::VTag(vtag) = a {
+        let events: Vec<_> = vtag.listeners.iter().map(|l| l.event_type()).collect();
+        assert_eq!(
+            events,
+            vec![
+                "dblclick",
+                "touchstart",
+                "wheel",
+                "animationend",
+                "transitionend",
+                "paste",
+                "compositionend",
+                "focusin",
+                "beforeinput",
+                "loadeddata",
+                "error",
+            ]
+        );
+    } else {
+        panic!("vtag expected");
+    }
+}
+
+#[test]
+fn it_sets_modifiers_of_listeners() {
+    let a: VNode<Comp> = html! {
+        <form onsubmit.stop.prevent=|_| (), onclick=|_| (), [custom].capture.once=|_| (),>
+        </form>
+    };
+    if let VNode::VTag(vtag) = a {
+        let modifiers: Vec<_> = vtag.listeners.iter().map(|l| l.modifiers()).collect();
+        assert_eq!(
+            modifiers,
+            vec![
+                Modifiers {
+                    stop: true,
+                    prevent: true,
+                    ..Modifiers::default()
+                },
+                Modifiers::default(),
+                Modifiers {
+                    capture: true,
+                    once: true,
+                    ..Modifiers::default()
+                },
+            ]
+        );
+        assert_eq!(vtag.listeners[2].event_type(), "custom");
+    } else {
+        panic!("vtag expected");
+    }
+}